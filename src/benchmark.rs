@@ -0,0 +1,262 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use crate::{get_config_dir, get_init_system, lint, log_to_file, t, EXPECTED_STOP};
+
+const BENCH_QNUM: u32 = 237;
+const BENCH_MARK: &str = "0x40000000";
+
+pub enum BenchMsg {
+    Progress(String),
+    Finished(io::Result<Vec<BenchmarkResult>>),
+}
+
+#[derive(Clone)]
+pub struct BenchmarkResult {
+    pub strategy: String,
+    pub score: f64,
+    pub handshake_ms: f64,
+    pub ttfb_ms: f64,
+    pub speed: f64,
+}
+
+pub fn get_scores_path() -> PathBuf {
+    get_config_dir().join("strategy_scores.txt")
+}
+
+// Kept in the config directory so no other user can swap the script pkexec runs.
+fn get_script_path() -> PathBuf {
+    get_config_dir().join("benchmark.sh")
+}
+
+fn get_cancel_path() -> PathBuf {
+    get_config_dir().join("benchmark.cancel")
+}
+
+fn write_script(path: &Path, script: &str) -> io::Result<()> {
+    let _ = fs::remove_file(path);
+    let mut file = OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?;
+    file.write_all(script.as_bytes())
+}
+
+pub fn load_scores() -> Vec<BenchmarkResult> {
+    let content = fs::read_to_string(get_scores_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(5, '\t').collect();
+            if parts.len() != 5 {
+                return None;
+            }
+            Some(BenchmarkResult {
+                score: parts[0].parse().ok()?,
+                handshake_ms: parts[1].parse().ok()?,
+                ttfb_ms: parts[2].parse().ok()?,
+                speed: parts[3].parse().ok()?,
+                strategy: parts[4].to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn save_scores(results: &[BenchmarkResult]) -> io::Result<()> {
+    let mut merged = load_scores();
+    merged.retain(|old| !results.iter().any(|r| r.strategy == old.strategy));
+    merged.extend(results.iter().cloned());
+    let mut content = String::new();
+    for r in merged {
+        content.push_str(&format!("{:.1}\t{:.1}\t{:.1}\t{:.0}\t{}\n", r.score, r.handshake_ms, r.ttfb_ms, r.speed, r.strategy));
+    }
+    fs::write(get_scores_path(), content)
+}
+
+pub fn score_for(scores: &[BenchmarkResult], strategy: &str) -> Option<f64> {
+    scores.iter().find(|r| r.strategy == strategy).map(|r| r.score)
+}
+
+pub fn sort_by_score(strategies: &mut [String]) {
+    let scores = load_scores();
+    strategies.sort_by(|a, b| {
+        let sa = score_for(&scores, a).unwrap_or(-1.0);
+        let sb = score_for(&scores, b).unwrap_or(-1.0);
+        sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+fn service_commands() -> (&'static str, &'static str, &'static str) {
+    match get_init_system().as_str() {
+        "openrc" => ("rc-service zapret status >/dev/null 2>&1", "rc-service zapret stop", "rc-service zapret start"),
+        "runit" => ("sv status zapret 2>/dev/null | grep -q '^run:'", "sv down zapret", "sv up zapret"),
+        _ => ("systemctl is-active --quiet zapret", "systemctl stop zapret", "systemctl start zapret"),
+    }
+}
+
+fn build_script(nfqws: &str, strategies: &[String], domains: &[Vec<String>], runs: u32) -> String {
+    let (is_active, stop, start) = service_commands();
+    let cancel = shell_quote(&get_cancel_path().to_string_lossy());
+    let rule = format!("-p tcp -m multiport --dports 80,443 -m mark ! --mark {m}/{m} -j NFQUEUE --queue-num {q} --queue-bypass", m = BENCH_MARK, q = BENCH_QNUM);
+    let mut script = String::from("#!/bin/sh\nexec 2>&1\n");
+    script.push_str(&format!("rm -f {}\n", cancel));
+    script.push_str("remove_rules() {\n");
+    script.push_str(&format!("  if command -v iptables >/dev/null 2>&1; then while iptables -t mangle -D OUTPUT {} 2>/dev/null; do :; done; fi\n", rule));
+    script.push_str("  if command -v nft >/dev/null 2>&1; then nft delete table inet zapretgtk_bench 2>/dev/null; fi\n");
+    script.push_str("}\n");
+    script.push_str("remove_rules\n");
+    script.push_str(&format!("WAS_ACTIVE=0\nif {}; then WAS_ACTIVE=1; {}; fi\n", is_active, stop));
+    script.push_str("PID=\"\"\n");
+    script.push_str("cleanup() {\n");
+    script.push_str("  [ -n \"$PID\" ] && kill \"$PID\" 2>/dev/null\n");
+    script.push_str("  remove_rules\n");
+    script.push_str(&format!("  rm -f {}\n", cancel));
+    script.push_str(&format!("  if [ \"$WAS_ACTIVE\" = 1 ]; then {}; fi\n", start));
+    script.push_str("}\n");
+    script.push_str("trap cleanup EXIT\ntrap 'exit 1' INT TERM\n");
+    script.push_str("if command -v iptables >/dev/null 2>&1; then\n");
+    script.push_str(&format!("  iptables -t mangle -I OUTPUT {}\n", rule));
+    script.push_str("else\n");
+    script.push_str("  nft add table inet zapretgtk_bench\n");
+    script.push_str("  nft add chain inet zapretgtk_bench output '{ type filter hook output priority mangle; }'\n");
+    script.push_str(&format!("  nft add rule inet zapretgtk_bench output 'meta mark and {m} == 0 tcp dport {{ 80, 443 }} queue num {q} bypass'\n", m = BENCH_MARK, q = BENCH_QNUM));
+    script.push_str("fi\n");
+    for (i, strategy) in strategies.iter().enumerate() {
+        let args: Vec<String> = strategy.split_whitespace().map(shell_quote).collect();
+        script.push_str(&format!("echo \"BENCH_STRATEGY:{}\"\n", i));
+        script.push_str(&format!("\"{}\" --qnum={} --dpi-desync-fwmark={} {} >/dev/null 2>&1 &\nPID=$!\nsleep 1\n", nfqws, BENCH_QNUM, BENCH_MARK, args.join(" ")));
+        for domain in &domains[i] {
+            for _ in 0..runs {
+                script.push_str(&format!("[ -e {} ] && exit 0\n", cancel));
+                script.push_str(&format!(
+                    "echo \"BENCH:{}:{}:$(curl -so /dev/null --max-time 15 -w '%{{http_code}} %{{time_connect}} %{{time_appconnect}} %{{time_starttransfer}} %{{speed_download}}' https://{}/)\"\n",
+                    i, domain, domain
+                ));
+            }
+        }
+        script.push_str("kill \"$PID\" 2>/dev/null\nwait \"$PID\" 2>/dev/null\nPID=\"\"\n");
+    }
+    script
+}
+
+struct Sample {
+    ok: bool,
+    handshake: f64,
+    ttfb: f64,
+    speed: f64,
+}
+
+fn parse_sample(values: &str) -> Option<Sample> {
+    let fields: Vec<&str> = values.split_whitespace().collect();
+    if fields.len() != 5 {
+        return None;
+    }
+    let num = |s: &str| s.replace(',', ".").parse::<f64>().unwrap_or(0.0);
+    Some(Sample {
+        ok: fields[0] != "000",
+        handshake: (num(fields[2]) - num(fields[1])).max(0.0),
+        ttfb: num(fields[3]),
+        speed: num(fields[4]),
+    })
+}
+
+fn summarize(strategy: &str, samples: &[Sample]) -> BenchmarkResult {
+    let ok: Vec<&Sample> = samples.iter().filter(|s| s.ok).collect();
+    if ok.is_empty() {
+        return BenchmarkResult { strategy: strategy.to_string(), score: 0.0, handshake_ms: 0.0, ttfb_ms: 0.0, speed: 0.0 };
+    }
+    let n = ok.len() as f64;
+    let handshake_ms = ok.iter().map(|s| s.handshake).sum::<f64>() / n * 1000.0;
+    let ttfb_ms = ok.iter().map(|s| s.ttfb).sum::<f64>() / n * 1000.0;
+    let speed = ok.iter().map(|s| s.speed).sum::<f64>() / n;
+    let success = n / samples.len() as f64;
+    let latency_part = 1000.0 / (1000.0 + handshake_ms + ttfb_ms);
+    let speed_part = speed / (speed + 1_000_000.0);
+    BenchmarkResult {
+        strategy: strategy.to_string(),
+        score: success * (50.0 * latency_part + 50.0 * speed_part),
+        handshake_ms,
+        ttfb_ms,
+        speed,
+    }
+}
+
+pub fn run_benchmark(strategies: Vec<String>, domains: Vec<Vec<String>>, runs: u32, sender: mpsc::Sender<BenchMsg>, cancel_flag: Arc<AtomicBool>) {
+    let nfqws = match lint::get_nfqws_binary() {
+        Some(p) => p,
+        None => {
+            let _ = sender.send(BenchMsg::Finished(Err(io::Error::new(io::ErrorKind::NotFound, t("nfqws bulunamadı. Önce Zapret'i kurun.")))));
+            return;
+        }
+    };
+    let script = build_script(&nfqws.to_string_lossy(), &strategies, &domains, runs);
+    let script_path = get_script_path();
+    let cancel_path = get_cancel_path();
+    let _ = fs::remove_file(&cancel_path);
+    if let Err(e) = write_script(&script_path, &script) {
+        let _ = sender.send(BenchMsg::Finished(Err(e)));
+        return;
+    }
+    EXPECTED_STOP.store(true, Ordering::Relaxed);
+    log_to_file(&format!("Benchmark started for {} strategies, {} runs.", strategies.len(), runs));
+    let mut child = match Command::new("pkexec").arg("/bin/sh").arg(&script_path).stdout(Stdio::piped()).spawn() {
+        Ok(c) => c,
+        Err(e) => {
+            let _ = sender.send(BenchMsg::Finished(Err(e)));
+            return;
+        }
+    };
+    let mut samples: Vec<Vec<Sample>> = strategies.iter().map(|_| Vec::new()).collect();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            // The script runs as root, so it is asked to stop itself and undo its firewall changes.
+            if cancel_flag.load(Ordering::Relaxed) && !cancel_path.exists() {
+                let _ = fs::write(&cancel_path, "");
+            }
+            let Ok(line) = line else { break };
+            log_to_file(&format!("[BENCH]: {}", line));
+            if let Some(idx) = line.strip_prefix("BENCH_STRATEGY:") {
+                let idx: usize = idx.trim().parse().unwrap_or(0);
+                let _ = sender.send(BenchMsg::Progress(t("Test edilen strateji: {c}/{n}").replace("{c}", &(idx + 1).to_string()).replace("{n}", &strategies.len().to_string())));
+            } else if let Some(rest) = line.strip_prefix("BENCH:") {
+                let mut parts = rest.splitn(3, ':');
+                let idx = parts.next().and_then(|i| i.parse::<usize>().ok());
+                let _domain = parts.next();
+                if let (Some(idx), Some(sample)) = (idx, parts.next().and_then(parse_sample)) {
+                    if let Some(list) = samples.get_mut(idx) {
+                        list.push(sample);
+                    }
+                }
+            }
+        }
+    }
+    let status = child.wait();
+    let _ = fs::remove_file(&script_path);
+    if cancel_flag.load(Ordering::Relaxed) {
+        log_to_file("Benchmark cancelled.");
+        return;
+    }
+    match status {
+        Ok(s) if s.success() => {
+            let mut results: Vec<BenchmarkResult> = strategies
+                .iter()
+                .zip(samples.iter())
+                .map(|(strategy, list)| summarize(strategy, list))
+                .collect();
+            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+            log_to_file(&format!("Benchmark completed. {} strategies scored.", results.len()));
+            let _ = sender.send(BenchMsg::Finished(Ok(results)));
+        },
+        Ok(s) => {
+            let _ = sender.send(BenchMsg::Finished(Err(io::Error::other(t("Hız testi başarısız. Kod: {}").replace("{}", &s.code().unwrap_or(-1).to_string())))));
+        },
+        Err(e) => {
+            let _ = sender.send(BenchMsg::Finished(Err(e)));
+        }
+    }
+}
@@ -0,0 +1,477 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::benchmark::shell_quote;
+use crate::{get_config_dir, get_init_system, log_to_file, rules, t};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn label(self) -> String {
+        match self {
+            Severity::Low => t("Düşük"),
+            Severity::Medium => t("Orta"),
+            Severity::High => t("Yüksek"),
+        }
+    }
+
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Severity::Low => "dim-label",
+            Severity::Medium => "warning",
+            Severity::High => "error",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Remedy {
+    pub label: String,
+    pub description: String,
+    pub command: String,
+    pub undo: String,
+    pub root: bool,
+}
+
+pub struct Conflict {
+    pub title: String,
+    pub explanation: String,
+    pub severity: Severity,
+    pub remedy: Option<Remedy>,
+}
+
+impl Conflict {
+    // Low findings are informational and do not hold up the check.
+    pub fn blocks(&self) -> bool {
+        self.severity > Severity::Low
+    }
+}
+
+pub struct Record {
+    pub description: String,
+    pub undo: String,
+    pub root: bool,
+}
+
+fn first_pid(process: &str) -> Option<String> {
+    let output = Command::new("pgrep").arg("-x").arg(process).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).lines().next().map(|l| l.trim().to_string())
+}
+
+fn systemd_unit(pid: &str) -> Option<(String, bool)> {
+    let cgroup = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let path = cgroup
+        .lines()
+        .find_map(|l| l.strip_prefix("0::").or_else(|| l.split_once(":name=systemd:").map(|(_, p)| p)))?;
+    let unit = path.rsplit('/').find(|seg| seg.ends_with(".service"))?;
+    if unit.starts_with("user@") {
+        return None;
+    }
+    Some((unit.to_string(), path.contains("/user@")))
+}
+
+fn systemd_remedy(unit: &str, user: bool) -> Remedy {
+    let systemctl = if user { "systemctl --user" } else { "systemctl" };
+    let enabled = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} is-enabled --quiet {}", systemctl, shell_quote(unit)))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    let (command, undo, label) = if enabled {
+        (
+            format!("{0} stop {1} && {0} disable {1}", systemctl, shell_quote(unit)),
+            format!("{0} enable {1} && {0} start {1}", systemctl, shell_quote(unit)),
+            t("Servisi durdur ve devre dışı bırak"),
+        )
+    } else {
+        (
+            format!("{} stop {}", systemctl, shell_quote(unit)),
+            format!("{} start {}", systemctl, shell_quote(unit)),
+            t("Servisi durdur"),
+        )
+    };
+    Remedy {
+        label,
+        description: t("{} servisi durduruldu").replace("{}", unit),
+        command,
+        undo,
+        root: !user,
+    }
+}
+
+fn service_remedy(pid: &str, process: &str) -> Option<Remedy> {
+    if let Some((unit, user)) = systemd_unit(pid) {
+        return Some(systemd_remedy(&unit, user));
+    }
+    let name = shell_quote(process);
+    match get_init_system().as_str() {
+        "openrc" if Path::new("/etc/init.d").join(process).exists() => Some(Remedy {
+            label: t("Servisi durdur ve devre dışı bırak"),
+            description: t("{} servisi durduruldu").replace("{}", process),
+            command: format!("rc-service {0} stop; rc-update del {0} default", name),
+            undo: format!("rc-update add {0} default; rc-service {0} start", name),
+            root: true,
+        }),
+        "runit" if Path::new("/var/service").join(process).exists() => Some(Remedy {
+            label: t("Servisi durdur"),
+            description: t("{} servisi durduruldu").replace("{}", process),
+            command: format!("sv down {}", name),
+            undo: format!("sv up {}", name),
+            root: true,
+        }),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Category {
+    Dpi,
+    Vpn,
+    Proxy,
+}
+
+const KNOWN_PROCESSES: &[(&str, &str, Category)] = &[
+    ("tpws", "tpws", Category::Dpi),
+    ("nfqws", "nfqws", Category::Dpi),
+    ("dvtws", "dvtws", Category::Dpi),
+    ("winws", "winws", Category::Dpi),
+    ("goodbyedpi", "GoodbyeDPI", Category::Dpi),
+    ("ciadpi", "ByeDPI", Category::Dpi),
+    ("byedpi", "ByeDPI", Category::Dpi),
+    ("spoofdpi", "SpoofDPI", Category::Dpi),
+    ("openvpn", "OpenVPN", Category::Vpn),
+    ("wireguard-go", "WireGuard", Category::Vpn),
+    ("warp-svc", "Cloudflare WARP", Category::Vpn),
+    ("tailscaled", "Tailscale", Category::Vpn),
+    ("zerotier-one", "ZeroTier", Category::Vpn),
+    ("sing-box", "sing-box", Category::Proxy),
+    ("xray", "Xray", Category::Proxy),
+    ("v2ray", "V2Ray", Category::Proxy),
+    ("mihomo", "Mihomo", Category::Proxy),
+    ("clash", "Clash", Category::Proxy),
+];
+
+fn process_conflicts() -> Vec<(&'static str, Conflict)> {
+    let mut conflicts = Vec::new();
+    for (process, display, category) in KNOWN_PROCESSES {
+        let Some(pid) = first_pid(process) else {
+            continue;
+        };
+        let (severity, explanation) = match category {
+            Category::Dpi => (
+                Severity::High,
+                t("Başka bir DPI atlatma aracı çalışıyor. İki araç aynı bağlantıları değiştirirse ikisi de bozulur ve strateji testleri yanlış sonuç verir."),
+            ),
+            Category::Vpn => (
+                Severity::Medium,
+                t("VPN çalışıyor. Tünelden geçen trafik zapret'e uğramaz, bu yüzden strateji testleri yanıltıcı olabilir."),
+            ),
+            Category::Proxy => (
+                Severity::Medium,
+                t("Proxy/tünel aracı çalışıyor. TUN modu açıksa trafik zapret'in kurallarını atlar."),
+            ),
+        };
+        conflicts.push((*display, Conflict {
+            title: t("{} işlemi ({})").replacen("{}", display, 1).replacen("{}", process, 1),
+            explanation,
+            severity,
+            remedy: service_remedy(&pid, process),
+        }));
+    }
+    conflicts
+}
+
+// Follows policy routing; wg-quick full tunnels leave the main table alone.
+fn default_route_device() -> Option<String> {
+    let output = Command::new("ip").args(["route", "get", "1.1.1.1"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let idx = tokens.iter().position(|tok| *tok == "dev")?;
+    tokens.get(idx + 1).map(|dev| dev.to_string())
+}
+
+fn nm_vpn_conflicts(route_dev: Option<&str>, devices: &mut Vec<String>) -> Vec<Conflict> {
+    let Ok(output) = Command::new("nmcli")
+        .args(["-t", "-f", "NAME,TYPE,DEVICE", "connection", "show", "--active"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    let mut conflicts = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.rsplitn(3, ':');
+        let (Some(device), Some(kind), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if kind != "vpn" && kind != "wireguard" {
+            continue;
+        }
+        let name = name.replace("\\:", ":");
+        let severity = if kind == "wireguard" && route_dev == Some(device) { Severity::High } else { Severity::Medium };
+        devices.push(device.to_string());
+        conflicts.push(Conflict {
+            title: t("NetworkManager VPN bağlantısı: {}").replace("{}", &name),
+            explanation: t("Bu bağlantı etkinken trafik tünelden çıkar ve zapret'in DPI atlatması uygulanmaz."),
+            severity,
+            remedy: Some(Remedy {
+                label: t("Bağlantıyı kapat"),
+                description: t("{} VPN bağlantısı kapatıldı").replace("{}", &name),
+                command: format!("nmcli connection down id {}", shell_quote(&name)),
+                undo: format!("nmcli connection up id {}", shell_quote(&name)),
+                root: false,
+            }),
+        });
+    }
+    conflicts
+}
+
+fn interface_conflicts(route_dev: Option<&str>, skip: &[String]) -> Vec<(&'static str, Conflict)> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut conflicts = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if skip.contains(&name) {
+            continue;
+        }
+        let path = entry.path();
+        let uevent = fs::read_to_string(path.join("uevent")).unwrap_or_default();
+        let kind = if uevent.lines().any(|l| l == "DEVTYPE=wireguard") {
+            "WireGuard"
+        } else if name.starts_with("tailscale") {
+            "Tailscale"
+        } else if path.join("tun_flags").exists() {
+            "TUN"
+        } else {
+            continue;
+        };
+        let title = t("{} arayüzü: {}").replacen("{}", kind, 1).replacen("{}", &name, 1);
+        let wg_quick = format!("wg-quick@{}.service", name);
+        let remedy = Command::new("systemctl")
+            .args(["is-active", "--quiet", &wg_quick])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+            .then(|| systemd_remedy(&wg_quick, false));
+        let conflict = if route_dev == Some(name.as_str()) {
+            Conflict {
+                title,
+                explanation: t("İnternet trafiği bu tünel arayüzünden çıkıyor. Zapret tünelin dışındaki trafiği işlediği için etkisiz kalır."),
+                severity: Severity::High,
+                remedy,
+            }
+        } else {
+            Conflict {
+                title,
+                explanation: t("Tünel arayüzü mevcut ancak varsayılan rota değil. Yalnızca bu tünele yönlendirilen trafik zapret'i atlar."),
+                severity: Severity::Low,
+                remedy,
+            }
+        };
+        conflicts.push((kind, conflict));
+    }
+    conflicts
+}
+
+// The netlink port id is the listener pid for the first socket it opens.
+fn bound_queues() -> Vec<(u32, String, String)> {
+    let content = fs::read_to_string("/proc/net/netfilter/nfnetlink_queue").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let queue = fields.next()?.parse().ok()?;
+            let pid = fields.next()?;
+            let name = fs::read_to_string(format!("/proc/{}/comm", pid))
+                .map(|c| c.trim().to_string())
+                .unwrap_or_else(|_| pid.to_string());
+            Some((queue, pid.to_string(), name))
+        })
+        .collect()
+}
+
+// NFQUEUE rules pin the kernel module, so a zero refcount spares a root prompt.
+fn queue_rules_loaded() -> bool {
+    ["xt_NFQUEUE", "nft_queue"].iter().any(|module| {
+        fs::read_to_string(format!("/sys/module/{}/refcnt", module))
+            .ok()
+            .and_then(|c| c.trim().parse::<u32>().ok())
+            .map(|count| count > 0)
+            .unwrap_or(false)
+    })
+}
+
+fn queue_conflicts() -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let bound = bound_queues();
+    for (queue, pid, name) in &bound {
+        if name == "nfqws" {
+            continue;
+        }
+        conflicts.push(Conflict {
+            title: t("NFQUEUE {q} dinleyicisi: {p}").replace("{q}", &queue.to_string()).replace("{p}", name),
+            explanation: t("Başka bir program paket kuyruğu kullanıyor. Aynı kuyruk numarası zapret ile çakışırsa paketler yanlış programa gider."),
+            severity: if *queue == rules::configured_qnum() { Severity::High } else { Severity::Medium },
+            remedy: service_remedy(pid, name),
+        });
+    }
+    // Reading the rules needs a pkexec prompt; not worth it while nfqws is the only listener.
+    let only_nfqws = !bound.is_empty() && bound.iter().all(|(_, _, name)| name == "nfqws");
+    if only_nfqws || !queue_rules_loaded() {
+        return conflicts;
+    }
+    let queue_rules = match rules::inspect() {
+        Ok(queue_rules) => queue_rules,
+        Err(e) => {
+            log_to_file(&format!("Conflict check could not read NFQUEUE rules: {}", e));
+            return conflicts;
+        }
+    };
+    let qnum = rules::configured_qnum();
+    for rule in queue_rules {
+        let first_queue: u32 = rule.queue.split([':', '-']).next().and_then(|q| q.parse().ok()).unwrap_or(0);
+        let listening = bound.iter().any(|(q, _, _)| *q == first_queue);
+        let title = t("NFQUEUE {q} kuralı ({l})").replace("{q}", &rule.queue).replace("{l}", &rule.location());
+        if !listening {
+            let bypass = rule.spec.contains("bypass");
+            conflicts.push(Conflict {
+                title,
+                explanation: if bypass {
+                    t("Kuyruğu dinleyen program yok. Kural paketleri düşürmüyor ama eski bir kurulumdan kalmış olabilir.")
+                } else {
+                    t("Kuyruğu dinleyen program yok ve kuralda bypass seçeneği yok; eşleşen paketler düşürülüyor.")
+                },
+                severity: if bypass { Severity::Low } else { Severity::High },
+                remedy: rule.delete_and_restore_commands().map(|(command, undo)| Remedy {
+                    label: t("Kuralı kaldır"),
+                    description: t("NFQUEUE {q} kuralı kaldırıldı ({l})").replace("{q}", &rule.queue).replace("{l}", &rule.location()),
+                    command,
+                    undo,
+                    root: true,
+                }),
+            });
+        } else if !rule.is_zapret(qnum) {
+            conflicts.push(Conflict {
+                title,
+                explanation: t("Bu kural zapret'e ait değil. Başka bir program da aynı trafiği kuyruğa alıyor olabilir."),
+                severity: Severity::Medium,
+                remedy: None,
+            });
+        }
+    }
+    conflicts
+}
+
+pub fn detect() -> Vec<Conflict> {
+    let route_dev = default_route_device();
+    let mut nm_devices = Vec::new();
+    let nm_conflicts = nm_vpn_conflicts(route_dev.as_deref(), &mut nm_devices);
+    let mut processes = process_conflicts();
+    let listed = processes.len();
+    // A tunnel whose daemon is already listed only raises that entry's severity.
+    for (kind, conflict) in interface_conflicts(route_dev.as_deref(), &nm_devices) {
+        match processes[..listed].iter_mut().find(|(service, _)| *service == kind) {
+            Some((_, process)) => {
+                process.severity = process.severity.max(conflict.severity);
+                process.remedy = process.remedy.take().or(conflict.remedy);
+            },
+            None => processes.push((kind, conflict)),
+        }
+    }
+    let mut conflicts: Vec<Conflict> = processes.into_iter().map(|(_, conflict)| conflict).collect();
+    conflicts.extend(nm_conflicts);
+    conflicts.extend(queue_conflicts());
+    conflicts.sort_by_key(|c| std::cmp::Reverse(c.severity));
+    conflicts
+}
+
+pub fn get_records_path() -> PathBuf {
+    get_config_dir().join("remediations.txt")
+}
+
+pub fn load_records() -> Vec<Record> {
+    let content = fs::read_to_string(get_records_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let root = fields.next()? == "root";
+            let description = fields.next()?.to_string();
+            let undo = fields.next()?.to_string();
+            Some(Record { description, undo, root })
+        })
+        .collect()
+}
+
+fn run(command: &str, root: bool) -> io::Result<()> {
+    let output = if root {
+        Command::new("pkexec").arg("sh").arg("-c").arg(command).output()?
+    } else {
+        Command::new("sh").arg("-c").arg(command).output()?
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+    let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(io::Error::other(if err.is_empty() { t("Komut başarısız oldu.") } else { err }))
+}
+
+pub fn apply(remedy: &Remedy) -> io::Result<()> {
+    log_to_file(&format!("Remediation: {} ({})", remedy.description, remedy.command));
+    run(&remedy.command, remedy.root)?;
+    let mut content = fs::read_to_string(get_records_path()).unwrap_or_default();
+    content.push_str(&format!(
+        "{}\t{}\t{}\n",
+        if remedy.root { "root" } else { "user" },
+        remedy.description.replace(['\t', '\n'], " "),
+        remedy.undo.replace('\n', " ")
+    ));
+    fs::write(get_records_path(), content)
+}
+
+pub fn undo_all() -> io::Result<()> {
+    let records: Vec<Record> = load_records().into_iter().rev().collect();
+    let mut reverted = vec![false; records.len()];
+    let mut script = String::new();
+    for (i, record) in records.iter().enumerate() {
+        log_to_file(&format!("Reverting remediation: {} ({})", record.description, record.undo));
+        if record.root {
+            script.push_str(&format!("( {} ) >&2 && echo reverted:{}\n", record.undo, i));
+        } else {
+            reverted[i] = run(&record.undo, false).is_ok();
+        }
+    }
+    if !script.is_empty() {
+        let output = Command::new("pkexec").arg("sh").arg("-c").arg(&script).output()?;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some(flag) = line.strip_prefix("reverted:").and_then(|i| i.parse::<usize>().ok()).and_then(|i| reverted.get_mut(i)) {
+                *flag = true;
+            }
+        }
+    }
+    let mut content = String::new();
+    let mut failed = 0;
+    for (record, done) in records.iter().zip(&reverted).rev() {
+        if !done {
+            failed += 1;
+            content.push_str(&format!("{}\t{}\t{}\n", if record.root { "root" } else { "user" }, record.description, record.undo));
+        }
+    }
+    fs::write(get_records_path(), content)?;
+    if failed > 0 {
+        log_to_file(&format!("{} remediations could not be reverted", failed));
+        return Err(io::Error::other(t("{} değişiklik geri alınamadı.").replace("{}", &failed.to_string())));
+    }
+    Ok(())
+}
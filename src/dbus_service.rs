@@ -0,0 +1,110 @@
+use gtk4 as gtk;
+use gtk::{gio, glib};
+use gtk::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub const OBJECT_PATH: &str = "/com/ornek/zapret_gtk/Service";
+pub const INTERFACE: &str = "com.ornek.ZapretGtk.Service";
+
+const SERVICE_XML: &str = r#"
+<node>
+  <interface name="com.ornek.ZapretGtk.Service">
+    <property name="Active" type="b" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="ActiveProfile" type="s" access="read"/>
+    <property name="Profiles" type="as" access="read"/>
+    <signal name="StateChanged">
+      <arg name="active" type="b"/>
+      <arg name="status" type="s"/>
+    </signal>
+    <signal name="ProfileChanged">
+      <arg name="profile" type="s"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+#[derive(Default)]
+struct ServiceState {
+    active: bool,
+    status: String,
+    active_profile: String,
+    profiles: Vec<String>,
+}
+
+pub struct ServiceObject {
+    connection: gio::DBusConnection,
+    state: Rc<RefCell<Option<ServiceState>>>,
+}
+
+fn property_value(state: &ServiceState, property: &str) -> glib::Variant {
+    match property {
+        "Active" => state.active.to_variant(),
+        "Status" => state.status.to_variant(),
+        "ActiveProfile" => state.active_profile.to_variant(),
+        "Profiles" => state.profiles.to_variant(),
+        _ => "".to_variant(),
+    }
+}
+
+impl ServiceObject {
+    pub fn register(connection: &gio::DBusConnection) -> Result<ServiceObject, glib::Error> {
+        let info = gio::DBusNodeInfo::for_xml(SERVICE_XML)?
+            .lookup_interface(INTERFACE)
+            .expect("service interface");
+        let state: Rc<RefCell<Option<ServiceState>>> = Rc::new(RefCell::new(None));
+        let property_state = state.clone();
+        connection
+            .register_object(OBJECT_PATH, &info)
+            .property(move |_, _, _, _, property| {
+                match property_state.borrow().as_ref() {
+                    Some(state) => property_value(state, property),
+                    None => property_value(&ServiceState::default(), property),
+                }
+            })
+            .build()?;
+        Ok(ServiceObject {
+            connection: connection.clone(),
+            state,
+        })
+    }
+
+    pub fn update(&self, active: bool, status: &str, profiles: Vec<String>, active_profile: Option<String>) {
+        let new_state = ServiceState {
+            active,
+            status: status.to_string(),
+            active_profile: active_profile.unwrap_or_default(),
+            profiles,
+        };
+        let old_state = self.state.borrow_mut().take();
+        let mut changed: HashMap<String, glib::Variant> = HashMap::new();
+        for property in ["Active", "Status", "ActiveProfile", "Profiles"] {
+            let value = property_value(&new_state, property);
+            if old_state.as_ref().map(|s| property_value(s, property)) != Some(value.clone()) {
+                changed.insert(property.to_string(), value);
+            }
+        }
+        let state_changed = old_state.as_ref().map(|s| s.active != active || s.status != status).unwrap_or(true);
+        let profile_changed = old_state.as_ref().map(|s| s.active_profile != new_state.active_profile).unwrap_or(false);
+        let profile = new_state.active_profile.clone();
+        *self.state.borrow_mut() = Some(new_state);
+        if changed.is_empty() {
+            return;
+        }
+        let _ = self.connection.emit_signal(
+            None,
+            OBJECT_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            Some(&(INTERFACE, changed, Vec::<String>::new()).to_variant()),
+        );
+        if state_changed {
+            let _ = self.connection.emit_signal(None, OBJECT_PATH, INTERFACE, "StateChanged", Some(&(active, status).to_variant()));
+        }
+        if profile_changed {
+            let _ = self.connection.emit_signal(None, OBJECT_PATH, INTERFACE, "ProfileChanged", Some(&(profile,).to_variant()));
+        }
+    }
+}
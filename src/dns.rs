@@ -0,0 +1,830 @@
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+use crate::benchmark::shell_quote;
+use crate::{get_config_dir, get_init_system, log_to_file, settings, t};
+
+pub const DNSCRYPT_CONFIG: &str = "/etc/dnscrypt-proxy/dnscrypt-proxy.toml";
+pub const RESOLVED_DROPIN: &str = "/etc/systemd/resolved.conf.d/zapret-gtk.conf";
+pub const RESOLV_CONF: &str = "/etc/resolv.conf";
+pub const RESOLV_CONF_BACKUP: &str = "/etc/resolv.conf.zapret-gtk";
+const CUSTOM_STATIC_PREFIX: &str = "zapret-gtk-custom";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Provider {
+    Cloudflare,
+    Quad9,
+    Google,
+    AdGuard,
+    Custom,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transport {
+    Plain,
+    Doh,
+    Dot,
+    DnsCrypt,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Backend {
+    NetworkManager,
+    Resolved,
+    ResolvConf,
+}
+
+pub const PROVIDERS: [Provider; 5] = [Provider::Cloudflare, Provider::Quad9, Provider::Google, Provider::AdGuard, Provider::Custom];
+pub const TRANSPORTS: [Transport; 4] = [Transport::Plain, Transport::Doh, Transport::Dot, Transport::DnsCrypt];
+pub const BACKEND_CHOICES: [&str; 4] = ["auto", "networkmanager", "resolved", "resolvconf"];
+
+impl Provider {
+    pub fn key(self) -> &'static str {
+        match self {
+            Provider::Cloudflare => "cloudflare",
+            Provider::Quad9 => "quad9",
+            Provider::Google => "google",
+            Provider::AdGuard => "adguard",
+            Provider::Custom => "custom",
+        }
+    }
+
+    pub fn display_name(self) -> String {
+        match self {
+            Provider::Cloudflare => "Cloudflare".to_string(),
+            Provider::Quad9 => "Quad9".to_string(),
+            Provider::Google => "Google".to_string(),
+            Provider::AdGuard => "AdGuard".to_string(),
+            Provider::Custom => t("Özel"),
+        }
+    }
+
+    fn ipv4(self) -> &'static [&'static str] {
+        match self {
+            Provider::Cloudflare => &["1.1.1.1", "1.0.0.1"],
+            Provider::Quad9 => &["9.9.9.9", "149.112.112.112"],
+            Provider::Google => &["8.8.8.8", "8.8.4.4"],
+            Provider::AdGuard => &["94.140.14.14", "94.140.15.15"],
+            Provider::Custom => &[],
+        }
+    }
+
+    fn ipv6(self) -> &'static [&'static str] {
+        match self {
+            Provider::Cloudflare => &["2606:4700:4700::1111", "2606:4700:4700::1001"],
+            Provider::Quad9 => &["2620:fe::fe", "2620:fe::9"],
+            Provider::Google => &["2001:4860:4860::8888", "2001:4860:4860::8844"],
+            Provider::AdGuard => &["2a10:50c0::ad1:ff", "2a10:50c0::ad2:ff"],
+            Provider::Custom => &[],
+        }
+    }
+
+    fn tls_name(self) -> Option<&'static str> {
+        match self {
+            Provider::Cloudflare => Some("cloudflare-dns.com"),
+            Provider::Quad9 => Some("dns.quad9.net"),
+            Provider::Google => Some("dns.google"),
+            Provider::AdGuard => Some("dns.adguard-dns.com"),
+            Provider::Custom => None,
+        }
+    }
+
+    fn resolver_name(self, transport: Transport) -> Option<&'static str> {
+        match (self, transport) {
+            (Provider::Cloudflare, Transport::Doh) => Some("cloudflare"),
+            (Provider::Quad9, Transport::Doh) => Some("quad9-doh-ip4-port443-filter-pri"),
+            (Provider::Quad9, Transport::DnsCrypt) => Some("quad9-dnscrypt-ip4-filter-pri"),
+            (Provider::Google, Transport::Doh) => Some("google"),
+            (Provider::AdGuard, Transport::Doh) => Some("adguard-dns-doh"),
+            (Provider::AdGuard, Transport::DnsCrypt) => Some("adguard-dns"),
+            _ => None,
+        }
+    }
+}
+
+impl Backend {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Backend::NetworkManager => "NetworkManager",
+            Backend::Resolved => "systemd-resolved",
+            Backend::ResolvConf => "/etc/resolv.conf",
+        }
+    }
+}
+
+pub fn network_manager_running() -> bool {
+    Command::new("nmcli")
+        .args(["-t", "-f", "RUNNING", "general"])
+        .output()
+        .map(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).trim() == "running")
+        .unwrap_or(false)
+}
+
+pub fn resolved_running() -> bool {
+    Command::new("systemctl")
+        .args(["is-active", "--quiet", "systemd-resolved"])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+// DNS-over-TLS needs resolved; otherwise NetworkManager owns DNS wherever it runs.
+pub fn detect_backend(transport: Transport) -> Backend {
+    let resolved = resolved_running();
+    if transport == Transport::Dot && resolved {
+        Backend::Resolved
+    } else if network_manager_running() {
+        Backend::NetworkManager
+    } else if resolved {
+        Backend::Resolved
+    } else {
+        Backend::ResolvConf
+    }
+}
+
+pub fn backend_override() -> String {
+    settings::get("dns_backend")
+        .filter(|v| BACKEND_CHOICES.contains(&v.as_str()))
+        .unwrap_or_else(|| "auto".to_string())
+}
+
+pub fn set_backend_override(choice: &str) -> io::Result<()> {
+    settings::set("dns_backend", choice)
+}
+
+pub fn resolve_backend(transport: Transport) -> Backend {
+    match backend_override().as_str() {
+        "networkmanager" => Backend::NetworkManager,
+        "resolved" => Backend::Resolved,
+        "resolvconf" => Backend::ResolvConf,
+        _ => detect_backend(transport),
+    }
+}
+
+pub fn describe_backend(transport: Transport) -> String {
+    let detected = detect_backend(transport).display_name();
+    match backend_override().as_str() {
+        "auto" => t("Algılanan: {}").replace("{}", detected),
+        _ => t("Elle seçildi: {} (algılanan: {})")
+            .replacen("{}", resolve_backend(transport).display_name(), 1)
+            .replacen("{}", detected, 1),
+    }
+}
+
+impl Transport {
+    pub fn key(self) -> &'static str {
+        match self {
+            Transport::Plain => "plain",
+            Transport::Doh => "doh",
+            Transport::Dot => "dot",
+            Transport::DnsCrypt => "dnscrypt",
+        }
+    }
+
+    pub fn display_name(self) -> String {
+        match self {
+            Transport::Plain => t("Düz (şifresiz)"),
+            Transport::Doh => "DNS-over-HTTPS".to_string(),
+            Transport::Dot => "DNS-over-TLS".to_string(),
+            Transport::DnsCrypt => "DNSCrypt".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DnsConfig {
+    pub provider: Provider,
+    pub transport: Transport,
+    pub custom: String,
+}
+
+pub fn load() -> DnsConfig {
+    let provider = settings::get("dns_provider")
+        .and_then(|k| PROVIDERS.iter().copied().find(|p| p.key() == k))
+        .unwrap_or(Provider::Cloudflare);
+    let transport = settings::get("dns_transport")
+        .and_then(|k| TRANSPORTS.iter().copied().find(|tr| tr.key() == k))
+        .unwrap_or(Transport::Plain);
+    DnsConfig {
+        provider,
+        transport,
+        custom: settings::get("dns_custom").unwrap_or_default(),
+    }
+}
+
+pub fn save(config: &DnsConfig) -> io::Result<()> {
+    settings::set("dns_provider", config.provider.key())?;
+    settings::set("dns_transport", config.transport.key())?;
+    settings::set("dns_custom", config.custom.trim())
+}
+
+impl DnsConfig {
+    fn custom_entries(&self) -> Vec<String> {
+        self.custom.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    pub fn uses_dnscrypt_proxy(&self) -> bool {
+        matches!(self.transport, Transport::Doh | Transport::DnsCrypt)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.transport == Transport::Dot && resolve_backend(self.transport) != Backend::Resolved {
+            return Err(t("DNS-over-TLS yalnızca systemd-resolved ile kullanılabilir; bu sistemde çalışmıyor veya başka bir arka uç seçili."));
+        }
+        if self.provider == Provider::Custom {
+            let entries = self.custom_entries();
+            if entries.is_empty() {
+                return Err(t("Özel sağlayıcı için sunucu adresi girin."));
+            }
+            for entry in &entries {
+                let ok = if self.uses_dnscrypt_proxy() {
+                    entry.starts_with("sdns://")
+                } else if self.transport == Transport::Dot {
+                    entry.split('#').next().is_some_and(|ip| ip.parse::<IpAddr>().is_ok())
+                } else {
+                    entry.parse::<IpAddr>().is_ok()
+                };
+                if !ok {
+                    return Err(t("Geçersiz sunucu: {}").replace("{}", entry));
+                }
+            }
+        } else if self.uses_dnscrypt_proxy() && self.provider.resolver_name(self.transport).is_none() {
+            return Err(t("{p} {t} sunmuyor.")
+                .replace("{p}", &self.provider.display_name())
+                .replace("{t}", &self.transport.display_name()));
+        }
+        Ok(())
+    }
+
+    pub fn system_servers_v4(&self) -> Vec<String> {
+        if self.uses_dnscrypt_proxy() {
+            return vec!["127.0.0.1".to_string()];
+        }
+        if self.provider == Provider::Custom {
+            return self.custom_entries().into_iter().filter(|e| e.parse::<std::net::Ipv4Addr>().is_ok()).collect();
+        }
+        self.provider.ipv4().iter().map(|s| s.to_string()).collect()
+    }
+
+    pub fn system_servers_v6(&self) -> Vec<String> {
+        if self.uses_dnscrypt_proxy() {
+            return vec!["::1".to_string()];
+        }
+        if self.provider == Provider::Custom {
+            return self.custom_entries().into_iter().filter(|e| e.parse::<std::net::Ipv6Addr>().is_ok()).collect();
+        }
+        self.provider.ipv6().iter().map(|s| s.to_string()).collect()
+    }
+
+    pub fn resolved_servers(&self) -> Vec<String> {
+        if self.uses_dnscrypt_proxy() {
+            return vec!["127.0.0.1".to_string(), "::1".to_string()];
+        }
+        if self.provider == Provider::Custom {
+            return self.custom_entries();
+        }
+        let servers = self.provider.ipv4().iter().chain(self.provider.ipv6());
+        match (self.transport, self.provider.tls_name()) {
+            (Transport::Dot, Some(name)) => servers.map(|s| format!("{}#{}", s, name)).collect(),
+            _ => servers.map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} · {}", self.provider.display_name(), self.transport.display_name())
+    }
+}
+
+pub fn edit_dnscrypt_toml(content: &str, config: &DnsConfig) -> Result<String, String> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .map_err(|e: toml_edit::TomlError| t("dnscrypt-proxy.toml okunamadı: {}").replace("{}", &e.to_string()))?;
+    let mut listen = Array::new();
+    listen.push("127.0.0.1:53");
+    listen.push("[::1]:53");
+    doc["listen_addresses"] = value(listen);
+    if !config.uses_dnscrypt_proxy() {
+        return Ok(doc.to_string());
+    }
+    match config.transport {
+        Transport::Doh => doc["doh_servers"] = value(true),
+        Transport::DnsCrypt => doc["dnscrypt_servers"] = value(true),
+        _ => {}
+    }
+    if let Some(static_table) = doc.get_mut("static").and_then(Item::as_table_mut) {
+        static_table.retain(|key, _| !key.starts_with(CUSTOM_STATIC_PREFIX));
+    }
+    let mut names = Array::new();
+    if config.provider == Provider::Custom {
+        if !doc.contains_key("static") {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            doc["static"] = Item::Table(table);
+        }
+        for (i, stamp) in config.custom_entries().iter().enumerate() {
+            let name = format!("{}-{}", CUSTOM_STATIC_PREFIX, i + 1);
+            let mut entry = Table::new();
+            entry["stamp"] = value(stamp.as_str());
+            doc["static"][name.as_str()] = Item::Table(entry);
+            names.push(name);
+        }
+    } else if let Some(name) = config.provider.resolver_name(config.transport) {
+        names.push(name);
+    }
+    doc["server_names"] = value(names);
+    Ok(doc.to_string())
+}
+
+pub fn read_dnscrypt_config() -> io::Result<String> {
+    fs::read_to_string(DNSCRYPT_CONFIG).or_else(|_| {
+        let out = Command::new("pkexec").arg("cat").arg(DNSCRYPT_CONFIG).output()?;
+        if out.status.success() {
+            Ok(String::from_utf8_lossy(&out.stdout).to_string())
+        } else {
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, t("Dosya okunamadı")))
+        }
+    })
+}
+
+pub fn prepare_dnscrypt_config(config: &DnsConfig) -> Result<Option<String>, String> {
+    if !std::path::Path::new(DNSCRYPT_CONFIG).exists() {
+        return Ok(None);
+    }
+    let content = read_dnscrypt_config().map_err(|e| e.to_string())?;
+    let edited = edit_dnscrypt_toml(&content, config)?;
+    let temp_path = "/tmp/zapret_dnscrypt_proxy.toml";
+    fs::write(temp_path, edited).map_err(|e| t("Geçici dosya oluşturulamadı: {}").replace("{}", &e.to_string()))?;
+    Ok(Some(format!("install -m 644 {} {} && rm -f {}\n", temp_path, DNSCRYPT_CONFIG, temp_path)))
+}
+
+pub fn restart_dnscrypt_command() -> &'static str {
+    match get_init_system().as_str() {
+        "openrc" => "rc-service dnscrypt-proxy restart",
+        "runit" => "sv restart dnscrypt-proxy",
+        _ => "systemctl restart dnscrypt-proxy.service",
+    }
+}
+
+pub struct NmConnection {
+    pub name: String,
+    pub uuid: String,
+    pub kind: String,
+    pub device: String,
+    pub active: bool,
+    pub autoconnect: bool,
+}
+
+pub struct ConnectionResult {
+    pub name: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+pub struct DnsBackup {
+    pub uuid: String,
+    pub name: String,
+    pub ipv4_dns: String,
+    pub ipv4_ignore_auto: String,
+    pub ipv6_dns: String,
+    pub ipv6_ignore_auto: String,
+    pub dns_over_tls: String,
+}
+
+fn split_terse(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    fields.last_mut().unwrap().push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+fn list_connections() -> Vec<NmConnection> {
+    let Ok(output) = Command::new("nmcli")
+        .args(["-t", "-f", "NAME,UUID,TYPE,DEVICE,ACTIVE,AUTOCONNECT", "connection", "show"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields = split_terse(line);
+            if fields.len() < 6 {
+                return None;
+            }
+            Some(NmConnection {
+                name: fields[0].clone(),
+                uuid: fields[1].clone(),
+                kind: fields[2].clone(),
+                device: fields[3].clone(),
+                active: fields[4] == "yes",
+                autoconnect: fields[5] == "yes",
+            })
+        })
+        .collect()
+}
+
+pub fn candidate_connections() -> Vec<NmConnection> {
+    list_connections()
+        .into_iter()
+        .filter(|c| c.active || c.autoconnect)
+        .filter(|c| !matches!(c.kind.as_str(), "loopback" | "vpn" | "wireguard" | "tun"))
+        .collect()
+}
+
+pub fn selected_uuids() -> Option<Vec<String>> {
+    settings::get("dns_connections").map(|v| v.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect())
+}
+
+pub fn set_selected_uuids(uuids: &[String]) -> io::Result<()> {
+    settings::set("dns_connections", &uuids.join(","))
+}
+
+pub fn is_selected(connection: &NmConnection) -> bool {
+    selected_uuids().map(|uuids| uuids.contains(&connection.uuid)).unwrap_or(true)
+}
+
+pub fn default_targets() -> Vec<NmConnection> {
+    candidate_connections().into_iter().filter(is_selected).collect()
+}
+
+pub fn get_backup_path() -> PathBuf {
+    get_config_dir().join("dns_backup.txt")
+}
+
+pub fn load_backups() -> Vec<DnsBackup> {
+    let content = fs::read_to_string(get_backup_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            Some(DnsBackup {
+                uuid: fields[0].to_string(),
+                name: fields[1].to_string(),
+                ipv4_dns: fields[2].to_string(),
+                ipv4_ignore_auto: fields[3].to_string(),
+                ipv6_dns: fields[4].to_string(),
+                ipv6_ignore_auto: fields[5].to_string(),
+                dns_over_tls: fields.get(6).unwrap_or(&"").to_string(),
+            })
+        })
+        .collect()
+}
+
+fn save_backups(backups: &[DnsBackup]) -> io::Result<()> {
+    let mut content = String::new();
+    for b in backups {
+        content.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            b.uuid, b.name.replace('\t', " "), b.ipv4_dns, b.ipv4_ignore_auto, b.ipv6_dns, b.ipv6_ignore_auto, b.dns_over_tls
+        ));
+    }
+    fs::write(get_backup_path(), content)
+}
+
+pub fn record_original(connection: &NmConnection) -> io::Result<()> {
+    let mut backups = load_backups();
+    if backups.iter().any(|b| b.uuid == connection.uuid) {
+        return Ok(());
+    }
+    let output = Command::new("nmcli")
+        .args(["-g", "ipv4.dns,ipv4.ignore-auto-dns,ipv6.dns,ipv6.ignore-auto-dns", "connection", "show", &connection.uuid])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    let values: Vec<String> = text.lines().map(|l| l.trim().to_string()).collect();
+    let field = |i: usize| values.get(i).cloned().unwrap_or_default();
+    // Older NetworkManager has no connection.dns-over-tls; empty leaves it alone on restore.
+    let dns_over_tls = Command::new("nmcli")
+        .args(["-g", "connection.dns-over-tls", "connection", "show", &connection.uuid])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    backups.push(DnsBackup {
+        uuid: connection.uuid.clone(),
+        name: connection.name.clone(),
+        ipv4_dns: field(0),
+        ipv4_ignore_auto: if field(1).is_empty() { "no".to_string() } else { field(1) },
+        ipv6_dns: field(2),
+        ipv6_ignore_auto: if field(3).is_empty() { "no".to_string() } else { field(3) },
+        dns_over_tls,
+    });
+    save_backups(&backups)
+}
+
+fn exists_or_symlink(path: &str) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+pub fn system_changes() -> Vec<String> {
+    let mut changes = Vec::new();
+    if exists_or_symlink(RESOLVED_DROPIN) {
+        changes.push(format!("systemd-resolved: {}", RESOLVED_DROPIN));
+    }
+    if exists_or_symlink(RESOLV_CONF_BACKUP) {
+        changes.push(t("{} (yedekten geri yüklenecek)").replace("{}", RESOLV_CONF));
+    }
+    changes.extend(networkd_dropins().into_iter().map(|d| format!("systemd-networkd: {}", d)));
+    changes
+}
+
+pub fn restore_commands() -> String {
+    let mut cmd = String::new();
+    if exists_or_symlink(RESOLVED_DROPIN) {
+        cmd.push_str(&format!(
+            "rm -f {}; for l in /sys/class/net/*; do resolvectl revert \"${{l##*/}}\" 2>/dev/null || true; done; systemctl restart systemd-resolved || true; ",
+            RESOLVED_DROPIN
+        ));
+    }
+    if exists_or_symlink(RESOLV_CONF_BACKUP) {
+        cmd.push_str(&format!("rm -f {0} && mv {1} {0} || true; ", RESOLV_CONF, RESOLV_CONF_BACKUP));
+    }
+    let dropins = networkd_dropins();
+    if !dropins.is_empty() {
+        let quoted: Vec<String> = dropins.iter().map(|d| shell_quote(d)).collect();
+        cmd.push_str(&format!("rm -f {}; networkctl reload || true; ", quoted.join(" ")));
+    }
+    for b in load_backups() {
+        let uuid = shell_quote(&b.uuid);
+        cmd.push_str(&format!(
+            "nmcli connection modify {} ipv4.dns {} ipv4.ignore-auto-dns {} ipv6.dns {} ipv6.ignore-auto-dns {} || true; ",
+            uuid,
+            shell_quote(&b.ipv4_dns),
+            shell_quote(&b.ipv4_ignore_auto),
+            shell_quote(&b.ipv6_dns),
+            shell_quote(&b.ipv6_ignore_auto)
+        ));
+        if !b.dns_over_tls.is_empty() {
+            cmd.push_str(&format!("nmcli connection modify {} connection.dns-over-tls {} || true; ", uuid, shell_quote(&b.dns_over_tls)));
+        }
+        cmd.push_str(&format!(
+            "if nmcli -t -f UUID connection show --active | grep -qx {0}; then nmcli connection up {0} >/dev/null 2>&1 || true; fi; ",
+            uuid
+        ));
+    }
+    cmd
+}
+
+pub fn restore() -> Result<(), String> {
+    let cmd = restore_commands();
+    if cmd.is_empty() {
+        return Err(t("Kayıtlı orijinal DNS ayarı yok."));
+    }
+    let output = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(&cmd)
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    save_backups(&[]).map_err(|e| e.to_string())
+}
+
+// Each connection reports `DNSRESULT ok|fail <uuid> <message>` for parse_results.
+pub fn nm_commands(config: &DnsConfig, connections: &[NmConnection]) -> String {
+    let v4 = shell_quote(&config.system_servers_v4().join(" "));
+    let v6_servers = config.system_servers_v6();
+    let mut cmd = String::new();
+    for connection in connections {
+        if let Err(e) = record_original(connection) {
+            log_to_file(&format!("Could not record DNS of {}: {}", connection.name, e));
+            cmd.push_str(&format!("echo \"DNSRESULT fail {} {}\"\n", connection.uuid, t("Orijinal DNS kaydedilemedi")));
+            continue;
+        }
+        let uuid = shell_quote(&connection.uuid);
+        cmd.push_str(&format!("if OUT=$(nmcli connection modify {} ipv4.dns {} ipv4.ignore-auto-dns yes 2>&1); then\n", uuid, v4));
+        if v6_servers.is_empty() {
+            cmd.push_str(&format!("  MSG={}\n", shell_quote(&t("IPv4 ayarlandı"))));
+        } else {
+            cmd.push_str(&format!("  case \"$(nmcli -g ipv6.method connection show {})\" in\n", uuid));
+            cmd.push_str(&format!("    ignore|disabled) MSG={} ;;\n", shell_quote(&t("IPv4 ayarlandı, IPv6 kapalı"))));
+            cmd.push_str(&format!(
+                "    *) if nmcli connection modify {} ipv6.dns {} ipv6.ignore-auto-dns yes >/dev/null 2>&1; then MSG={}; else MSG={}; fi ;;\n",
+                uuid,
+                shell_quote(&v6_servers.join(" ")),
+                shell_quote(&t("IPv4 ve IPv6 ayarlandı")),
+                shell_quote(&t("IPv4 ayarlandı, IPv6 ayarlanamadı"))
+            ));
+            cmd.push_str("  esac\n");
+        }
+        if config.transport == Transport::Dot {
+            cmd.push_str(&format!(
+                "  nmcli connection modify {} connection.dns-over-tls yes >/dev/null 2>&1 || MSG=\"$MSG, {}\"\n",
+                uuid,
+                t("DoT ayarlanamadı")
+            ));
+        }
+        if connection.active {
+            cmd.push_str(&format!("  nmcli connection up {} >/dev/null 2>&1 || true\n", uuid));
+        }
+        cmd.push_str(&format!("  echo \"DNSRESULT ok {} $MSG\"\n", connection.uuid));
+        cmd.push_str(&format!("else\n  echo \"DNSRESULT fail {} $OUT\" | head -n1\nfi\n", connection.uuid));
+    }
+    cmd
+}
+
+pub fn parse_results(output: &str, targets: &[(String, String)]) -> Vec<ConnectionResult> {
+    targets
+        .iter()
+        .map(|(id, name)| {
+            let line = output.lines().find_map(|l| {
+                let rest = l.strip_prefix("DNSRESULT ")?;
+                let (status, rest) = rest.split_once(' ')?;
+                let (uuid, message) = rest.split_once(' ').unwrap_or((rest, ""));
+                (uuid == id).then(|| (status == "ok", message.trim().to_string()))
+            });
+            let (ok, message) = line.unwrap_or((false, t("Sonuç alınamadı")));
+            ConnectionResult { name: name.clone(), ok, message }
+        })
+        .collect()
+}
+
+pub fn resolved_links() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut links: Vec<String> = entries
+        .flatten()
+        .filter(|e| {
+            let state = fs::read_to_string(e.path().join("operstate")).unwrap_or_default();
+            matches!(state.trim(), "up" | "unknown") && !e.path().join("tun_flags").exists()
+        })
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name != "lo" && !name.starts_with("wg") && !name.starts_with("tailscale"))
+        .collect();
+    links.sort();
+    links
+}
+
+const NETWORKD_DIR: &str = "/etc/systemd/network";
+const NETWORKD_DROPIN: &str = "zapret-gtk.conf";
+
+fn networkd_network_file(link: &str) -> Option<String> {
+    let output = Command::new("networkctl").args(["status", link]).output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Network File:"))
+        .map(|f| f.trim().to_string())
+        .filter(|f| f.ends_with(".network"))
+}
+
+fn networkd_dropins() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(NETWORKD_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path().join(NETWORKD_DROPIN))
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+// resolvectl alone is undone by the next DHCP renew; the networkd drop-in keeps it.
+pub fn resolved_commands(config: &DnsConfig, links: &[String]) -> String {
+    let servers = config.resolved_servers();
+    let dot = if config.transport == Transport::Dot { "yes" } else { "no" };
+    let mut cmd = String::new();
+    cmd.push_str("mkdir -p /etc/systemd/resolved.conf.d\n");
+    cmd.push_str(&format!(
+        "printf '%s\\n' '# zapret-gtk' '[Resolve]' {} 'DNSOverTLS={}' 'Domains=~.' > {}\n",
+        shell_quote(&format!("DNS={}", servers.join(" "))),
+        dot,
+        RESOLVED_DROPIN
+    ));
+    cmd.push_str(&format!(
+        "if OUT=$(systemctl restart systemd-resolved 2>&1); then echo \"DNSRESULT ok global {}\"; else echo \"DNSRESULT fail global $OUT\" | head -n1; fi\n",
+        t("Genel sunucular ayarlandı")
+    ));
+    let server_args: Vec<String> = servers.iter().map(|s| shell_quote(s)).collect();
+    for link in links {
+        let quoted = shell_quote(link);
+        cmd.push_str(&format!(
+            "if OUT=$(resolvectl dns {0} {1} 2>&1 && resolvectl domain {0} '~.' 2>&1 && resolvectl dnsovertls {0} {2} 2>&1); then echo \"DNSRESULT ok {3} {4}\"; else echo \"DNSRESULT fail {3} $OUT\" | head -n1; fi\n",
+            quoted,
+            server_args.join(" "),
+            dot,
+            link,
+            t("Bağlantı sunucuları ayarlandı")
+        ));
+        if let Some(file) = networkd_network_file(link) {
+            let name = Path::new(&file).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let dir = shell_quote(&format!("{}/{}.d", NETWORKD_DIR, name));
+            cmd.push_str(&format!(
+                "mkdir -p {0} && printf '%s\\n' '# zapret-gtk' '[Network]' 'DNSDefaultRoute=no' > {0}/{1}\n",
+                dir,
+                NETWORKD_DROPIN
+            ));
+        }
+    }
+    if links.iter().any(|l| networkd_network_file(l).is_some()) {
+        cmd.push_str("networkctl reload >/dev/null 2>&1 || true\n");
+    }
+    cmd
+}
+
+pub fn resolv_conf_commands(config: &DnsConfig) -> String {
+    let mut servers = config.system_servers_v4();
+    servers.extend(config.system_servers_v6());
+    servers.truncate(3);
+    let lines: Vec<String> = servers.iter().map(|s| shell_quote(&format!("nameserver {}", s))).collect();
+    format!(
+        "if [ ! -e {1} ] && [ ! -L {1} ] && {{ [ -e {0} ] || [ -L {0} ]; }}; then cp -P {0} {1}; fi\n\
+         if OUT=$(rm -f {0} 2>&1 && printf '%s\\n' '# zapret-gtk' {2} 2>&1 > {0}); then echo \"DNSRESULT ok resolvconf {3}\"; else echo \"DNSRESULT fail resolvconf $OUT\" | head -n1; fi\n",
+        RESOLV_CONF,
+        RESOLV_CONF_BACKUP,
+        lines.join(" "),
+        servers.join(" ")
+    )
+}
+
+pub fn backend_commands(config: &DnsConfig, backend: Backend) -> (String, Vec<(String, String)>) {
+    match backend {
+        Backend::NetworkManager => {
+            let connections = default_targets();
+            let targets = connections.iter().map(|c| (c.uuid.clone(), c.name.clone())).collect();
+            (nm_commands(config, &connections), targets)
+        }
+        Backend::Resolved => {
+            let mut targets = vec![("global".to_string(), t("systemd-resolved (genel)"))];
+            if network_manager_running() {
+                let connections = default_targets();
+                targets.extend(connections.iter().map(|c| (c.uuid.clone(), c.name.clone())));
+                return (resolved_commands(config, &[]) + &nm_commands(config, &connections), targets);
+            }
+            let links = resolved_links();
+            targets.extend(links.iter().map(|l| (l.clone(), l.clone())));
+            (resolved_commands(config, &links), targets)
+        }
+        Backend::ResolvConf => (
+            resolv_conf_commands(config),
+            vec![("resolvconf".to_string(), RESOLV_CONF.to_string())],
+        ),
+    }
+}
+
+pub fn configure_dnscrypt(config: &DnsConfig) -> Result<(), String> {
+    let Some(install) = prepare_dnscrypt_config(config)? else {
+        return Err(t("dnscrypt-proxy kurulu değil. Önce kurulumu tamamlayın."));
+    };
+    let script = format!("set -e\n{}{}\n", install, restart_dnscrypt_command());
+    let output = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(&script)
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+pub fn apply(config: &DnsConfig) -> Result<Vec<ConnectionResult>, String> {
+    config.validate()?;
+    let mut script = String::from("set -e\n");
+    if let Some(install) = prepare_dnscrypt_config(config)? {
+        script.push_str(&install);
+        if config.uses_dnscrypt_proxy() {
+            script.push_str(restart_dnscrypt_command());
+            script.push('\n');
+        }
+    } else if config.uses_dnscrypt_proxy() {
+        return Err(t("dnscrypt-proxy kurulu değil. Önce kurulumu tamamlayın."));
+    }
+    let (commands, targets) = backend_commands(config, resolve_backend(config.transport));
+    script.push_str("set +e\n");
+    script.push_str(&commands);
+    let output = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(&script)
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_results(&String::from_utf8_lossy(&output.stdout), &targets))
+}
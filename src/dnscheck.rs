@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use crate::{dns, domains, get_config_dir, presets, t};
+
+const MAX_DOMAINS: usize = 12;
+
+// Reached by address so the trusted answer never depends on the resolver under test.
+const TRUSTED_ENDPOINTS: [&str; 2] = ["https://1.1.1.1/dns-query", "https://8.8.8.8/resolve"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Ok,
+    NoAnswer,
+    Blackholed,
+    Spoofed,
+    Differs,
+    Unverified,
+}
+
+impl Verdict {
+    pub fn label(self) -> String {
+        match self {
+            Verdict::Ok => t("Doğru"),
+            Verdict::NoAnswer => t("Yanıt yok"),
+            Verdict::Blackholed => t("Engellenmiş (sahte adres)"),
+            Verdict::Spoofed => t("Sahte yanıt (engel sayfası)"),
+            Verdict::Differs => t("Farklı adres (CDN olabilir)"),
+            Verdict::Unverified => t("Doğrulanamadı"),
+        }
+    }
+
+    pub fn is_problem(self) -> bool {
+        matches!(self, Verdict::NoAnswer | Verdict::Blackholed | Verdict::Spoofed)
+    }
+}
+
+pub struct DomainCheck {
+    pub domain: String,
+    pub system: Vec<String>,
+    pub trusted: Vec<String>,
+    pub verdict: Verdict,
+}
+
+pub struct ProxyCheck {
+    pub answers: bool,
+    pub routed: bool,
+}
+
+pub struct Report {
+    pub checks: Vec<DomainCheck>,
+    pub nameservers: Vec<String>,
+    pub proxy: Option<ProxyCheck>,
+}
+
+impl Report {
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let poisoned: Vec<&str> = self
+            .checks
+            .iter()
+            .filter(|c| c.verdict.is_problem())
+            .map(|c| c.domain.as_str())
+            .collect();
+        if !poisoned.is_empty() {
+            problems.push(t("Sistem DNS'i şu alan adları için yanlış yanıt veriyor: {}").replace("{}", &poisoned.join(", ")));
+        }
+        if let Some(proxy) = &self.proxy {
+            if !proxy.answers {
+                problems.push(t("dnscrypt-proxy 127.0.0.1 üzerinde yanıt vermiyor."));
+            }
+            if !proxy.routed {
+                problems.push(t("Sistem sorguları dnscrypt-proxy üzerinden gitmiyor (kullanılan sunucular: {})")
+                    .replace("{}", &self.nameservers.join(", ")));
+            }
+        }
+        problems
+    }
+
+    pub fn verified(&self) -> bool {
+        self.checks.iter().any(|c| c.verdict != Verdict::Unverified)
+    }
+}
+
+pub fn check_domains() -> Vec<String> {
+    let mut list = domains::load_domains();
+    if list.is_empty() {
+        list = presets::load_presets()
+            .into_iter()
+            .filter_map(|p| p.domains.into_iter().next())
+            .collect();
+    }
+    list.truncate(MAX_DOMAINS);
+    list
+}
+
+fn system_answers(domain: &str) -> Vec<String> {
+    let Ok(output) = Command::new("getent").args(["ahostsv4", domain]).output() else {
+        return Vec::new();
+    };
+    let mut addresses: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.split_whitespace().next().map(|s| s.to_string()))
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+fn parse_doh_json(body: &str) -> Option<Vec<String>> {
+    if !body.contains("\"Status\"") {
+        return None;
+    }
+    let mut addresses = Vec::new();
+    if let Some(answer) = body.split("\"Answer\"").nth(1) {
+        for record in answer.split('{').skip(1) {
+            let compact: String = record.chars().filter(|c| !c.is_whitespace()).collect();
+            if !compact.contains("\"type\":1,") && !compact.contains("\"type\":1}") {
+                continue;
+            }
+            if let Some(data) = compact.split("\"data\":\"").nth(1).and_then(|d| d.split('"').next()) {
+                if data.parse::<Ipv4Addr>().is_ok() {
+                    addresses.push(data.to_string());
+                }
+            }
+        }
+    }
+    addresses.sort();
+    addresses.dedup();
+    Some(addresses)
+}
+
+fn trusted_answers(domain: &str) -> Option<Vec<String>> {
+    TRUSTED_ENDPOINTS.iter().find_map(|endpoint| {
+        let output = Command::new("curl")
+            .args(["-s", "--max-time", "8", "-H", "accept: application/dns-json"])
+            .arg(format!("{}?name={}&type=A", endpoint, domain))
+            .output()
+            .ok()?;
+        parse_doh_json(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+fn is_bogus(address: &str) -> bool {
+    let Ok(ip) = address.parse::<Ipv4Addr>() else {
+        return false;
+    };
+    let [a, b, ..] = ip.octets();
+    ip.is_unspecified() || ip.is_loopback() || ip.is_private() || ip.is_link_local() || (a == 100 && (64..128).contains(&b))
+}
+
+fn classify(system: &[String], trusted: Option<&[String]>) -> Verdict {
+    let Some(trusted) = trusted else {
+        return Verdict::Unverified;
+    };
+    if trusted.is_empty() {
+        return Verdict::Ok;
+    }
+    if system.is_empty() {
+        return Verdict::NoAnswer;
+    }
+    if system.iter().any(|a| is_bogus(a)) && !trusted.iter().any(|a| is_bogus(a)) {
+        return Verdict::Blackholed;
+    }
+    if system.iter().any(|a| trusted.contains(a)) {
+        Verdict::Ok
+    } else {
+        Verdict::Differs
+    }
+}
+
+pub fn system_nameservers() -> Vec<String> {
+    let content = fs::read_to_string(dns::RESOLV_CONF).unwrap_or_default();
+    let listed: Vec<String> = content
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("nameserver"))
+        .map(|s| s.trim().to_string())
+        .collect();
+    if !listed.iter().any(|s| s == "127.0.0.53") {
+        return listed;
+    }
+    let Ok(output) = Command::new("resolvectl").arg("dns").output() else {
+        return listed;
+    };
+    let mut servers: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.split_once(':').map(|(_, rest)| rest.to_string()))
+        .flat_map(|rest| rest.split_whitespace().map(|s| s.split('#').next().unwrap_or(s).to_string()).collect::<Vec<_>>())
+        .collect();
+    servers.sort();
+    servers.dedup();
+    servers
+}
+
+fn proxy_answers(domain: &str) -> bool {
+    Command::new("dig")
+        .args(["+short", "+time=3", "+tries=1", "@127.0.0.1", domain, "A"])
+        .output()
+        .map(|o| o.status.success() && !String::from_utf8_lossy(&o.stdout).trim().is_empty())
+        .unwrap_or(false)
+}
+
+pub fn run() -> Report {
+    let handles = check_domains()
+        .into_iter()
+        .map(|domain| {
+            let query = domain.clone();
+            (domain, thread::spawn(move || (system_answers(&query), trusted_answers(&query))))
+        })
+        .collect::<Vec<_>>();
+    let mut checks: Vec<DomainCheck> = handles
+        .into_iter()
+        .map(|(domain, handle)| {
+            let (system, trusted) = handle.join().unwrap_or_default();
+            let verdict = classify(&system, trusted.as_deref());
+            DomainCheck { domain, system, trusted: trusted.unwrap_or_default(), verdict }
+        })
+        .collect();
+    // One address handed out for several domains that DoH resolves elsewhere is a block page.
+    let mut shared: HashMap<&str, usize> = HashMap::new();
+    for check in checks.iter().filter(|c| c.verdict == Verdict::Differs) {
+        for address in &check.system {
+            *shared.entry(address.as_str()).or_default() += 1;
+        }
+    }
+    let block_pages: Vec<String> = shared.into_iter().filter(|(_, n)| *n >= 2).map(|(a, _)| a.to_string()).collect();
+    for check in checks.iter_mut().filter(|c| c.verdict == Verdict::Differs) {
+        if check.system.iter().any(|a| block_pages.contains(a)) {
+            check.verdict = Verdict::Spoofed;
+        }
+    }
+    let nameservers = system_nameservers();
+    let proxy_installed = std::path::Path::new(dns::DNSCRYPT_CONFIG).exists();
+    let proxy = (proxy_installed && dns::load().uses_dnscrypt_proxy()).then(|| ProxyCheck {
+        answers: checks.first().is_some_and(|c| proxy_answers(&c.domain)),
+        routed: !nameservers.is_empty() && nameservers.iter().all(|s| s == "127.0.0.1" || s == "::1"),
+    });
+    let report = Report { checks, nameservers, proxy };
+    if report.verified() {
+        let _ = fs::write(get_last_result_path(), report.problems().join("\n"));
+    }
+    report
+}
+
+pub fn get_last_result_path() -> PathBuf {
+    get_config_dir().join("dns_check.txt")
+}
+
+// The check sends domains to public resolvers, so only its cached result is used unprompted.
+pub fn last_problems() -> Vec<String> {
+    fs::read_to_string(get_last_result_path())
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use crate::{get_config_dir, t};
+
+pub fn get_domains_path() -> PathBuf {
+    get_config_dir().join("domains.txt")
+}
+
+pub fn load_domains() -> Vec<String> {
+    fs::read_to_string(get_domains_path())
+        .map(|content| content.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+pub fn save_domains(domains: &[String]) -> io::Result<()> {
+    let mut content = domains.join("\n");
+    content.push('\n');
+    fs::write(get_domains_path(), content)
+}
+
+pub fn get_strategy_domains_path() -> PathBuf {
+    get_config_dir().join("strategy_domains.txt")
+}
+
+pub fn get_tested_domains_path() -> PathBuf {
+    get_config_dir().join("tested_domains.txt")
+}
+
+fn load_pairs(path: PathBuf) -> Vec<(String, String)> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|l| l.split_once('\t'))
+                .map(|(domain, strategy)| (domain.trim().to_string(), strategy.trim().to_string()))
+                .filter(|(domain, strategy)| !domain.is_empty() && !strategy.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_pairs(path: PathBuf, pairs: &[(String, String)]) -> io::Result<()> {
+    let mut content = String::new();
+    for (domain, strategy) in pairs {
+        content.push_str(&format!("{}\t{}\n", domain, strategy));
+    }
+    fs::write(path, content)
+}
+
+pub fn load_strategy_domains() -> Vec<(String, String)> {
+    load_pairs(get_strategy_domains_path())
+}
+
+pub fn save_strategy_domains(pairs: &[(String, String)]) -> io::Result<()> {
+    save_pairs(get_strategy_domains_path(), pairs)
+}
+
+pub fn load_tested_domains() -> Vec<(String, String)> {
+    load_pairs(get_tested_domains_path())
+}
+
+pub fn save_tested_domains(pairs: &[(String, String)]) -> io::Result<()> {
+    save_pairs(get_tested_domains_path(), pairs)
+}
+
+pub fn domains_for_strategy(pairs: &[(String, String)], strategy: &str) -> Vec<String> {
+    pairs
+        .iter()
+        .filter(|(_, s)| s == strategy)
+        .map(|(d, _)| d.clone())
+        .collect()
+}
+
+pub fn split_domain_input(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+pub fn has_multiple_domains(text: &str) -> bool {
+    split_domain_input(text).len() > 1
+}
+
+pub fn normalize_domain(input: &str) -> Result<String, String> {
+    let mut host = input.trim().trim_matches(|c| c == '"' || c == '\'').to_lowercase();
+    if let Some(idx) = host.find("://") {
+        host = host[idx + 3..].to_string();
+    }
+    if let Some(idx) = host.find(['/', '?', '#']) {
+        host.truncate(idx);
+    }
+    if let Some(idx) = host.rfind('@') {
+        host = host[idx + 1..].to_string();
+    }
+    if host.starts_with('[') {
+        return Err(t("IP adresleri desteklenmiyor, alan adı girin."));
+    }
+    if let Some((name, port)) = host.rsplit_once(':') {
+        if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) {
+            host = name.to_string();
+        } else {
+            return Err(t("Geçersiz port veya karakter: ':'"));
+        }
+    }
+    let mut host = host.trim_end_matches('.');
+    for prefix in ["*.", "www."] {
+        if let Some(rest) = host.strip_prefix(prefix) {
+            host = rest;
+        }
+    }
+    if host.is_empty() {
+        return Err(t("Alan adı boş."));
+    }
+    let mut labels = Vec::new();
+    for label in host.split('.') {
+        if label.is_ascii() {
+            labels.push(label.to_string());
+        } else {
+            match punycode_encode(label) {
+                Some(encoded) => labels.push(format!("xn--{}", encoded)),
+                None => return Err(t("'{}' alan adına dönüştürülemedi.").replace("{}", label)),
+            }
+        }
+    }
+    let ascii = labels.join(".");
+    validate_hostname(&ascii)?;
+    Ok(ascii)
+}
+
+fn validate_hostname(host: &str) -> Result<(), String> {
+    if host.len() > 253 {
+        return Err(t("Alan adı çok uzun."));
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() < 2 {
+        return Err(t("Alan adı bir uzantı içermeli (örnek: google.com)."));
+    }
+    for label in &labels {
+        if label.is_empty() || label.len() > 63 {
+            return Err(t("Geçersiz alan adı bölümü: '{}'").replace("{}", label));
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(t("Geçersiz karakter içeriyor: '{}'").replace("{}", label));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(t("Bölümler '-' ile başlayamaz veya bitemez: '{}'").replace("{}", label));
+        }
+    }
+    if labels.iter().all(|l| l.chars().all(|c| c.is_ascii_digit())) {
+        return Err(t("IP adresleri desteklenmiyor, alan adı girin."));
+    }
+    Ok(())
+}
+
+const PUNY_BASE: u32 = 36;
+const PUNY_TMIN: u32 = 1;
+const PUNY_TMAX: u32 = 26;
+const PUNY_SKEW: u32 = 38;
+const PUNY_DAMP: u32 = 700;
+
+fn punycode_adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / PUNY_DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((PUNY_BASE - PUNY_TMIN) * PUNY_TMAX) / 2 {
+        delta /= PUNY_BASE - PUNY_TMIN;
+        k += PUNY_BASE;
+    }
+    k + ((PUNY_BASE - PUNY_TMIN + 1) * delta) / (delta + PUNY_SKEW)
+}
+
+fn punycode_digit(d: u32) -> char {
+    if d < 26 {
+        (b'a' + d as u8) as char
+    } else {
+        (b'0' + (d - 26) as u8) as char
+    }
+}
+
+fn punycode_encode(label: &str) -> Option<String> {
+    let code_points: Vec<u32> = label.chars().map(|c| c as u32).collect();
+    let mut output: String = label.chars().filter(|c| c.is_ascii()).collect();
+    let basic_len = output.len() as u32;
+    if basic_len > 0 {
+        output.push('-');
+    }
+    let mut n: u32 = 128;
+    let mut delta: u32 = 0;
+    let mut bias: u32 = 72;
+    let mut handled = basic_len;
+    while (handled as usize) < code_points.len() {
+        let m = *code_points.iter().filter(|&&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in &code_points {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = PUNY_BASE;
+                loop {
+                    let threshold = if k <= bias {
+                        PUNY_TMIN
+                    } else if k >= bias + PUNY_TMAX {
+                        PUNY_TMAX
+                    } else {
+                        k - bias
+                    };
+                    if q < threshold {
+                        break;
+                    }
+                    output.push(punycode_digit(threshold + (q - threshold) % (PUNY_BASE - threshold)));
+                    q = (q - threshold) / (PUNY_BASE - threshold);
+                    k += PUNY_BASE;
+                }
+                output.push(punycode_digit(q));
+                bias = punycode_adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+    Some(output)
+}
@@ -0,0 +1,236 @@
+use libadwaita as adw;
+use gtk4 as gtk;
+use adw::prelude::*;
+use gtk::{Box, Button, Label, Orientation, ScrolledWindow, StringList};
+use std::rc::Rc;
+use crate::nfqws::{self, StrategyFields};
+use crate::{lint, t};
+
+fn mode_combo(title: &str, modes: &[&str], current: Option<&str>) -> adw::ComboRow {
+    let mut items = vec![t("(yok)")];
+    items.extend(modes.iter().map(|m| m.to_string()));
+    let refs: Vec<&str> = items.iter().map(|s| s.as_str()).collect();
+    let selected = current
+        .and_then(|c| modes.iter().position(|m| *m == c))
+        .map(|i| i as u32 + 1)
+        .unwrap_or(0);
+    adw::ComboRow::builder()
+        .title(title)
+        .model(&StringList::new(&refs))
+        .selected(selected)
+        .build()
+}
+
+fn combo_value(combo: &adw::ComboRow, modes: &[&str]) -> Option<String> {
+    let idx = combo.selected();
+    if idx == 0 || idx == gtk::INVALID_LIST_POSITION {
+        return None;
+    }
+    modes.get(idx as usize - 1).map(|m| m.to_string())
+}
+
+pub fn show_strategy_editor(parent: &impl IsA<gtk::Widget>, strategy: &str, on_save: impl Fn(String) + 'static) {
+    let original = StrategyFields::parse(strategy);
+    let original_text = original.to_strategy();
+    let window = adw::Window::builder()
+        .title(t("Strateji Düzenleyici"))
+        .modal(true)
+        .default_width(520)
+        .default_height(640)
+        .build();
+    if let Some(root) = parent.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        window.set_transient_for(Some(&root));
+    }
+    let header = adw::HeaderBar::new();
+    let save_button = Button::builder()
+        .label(t("Yeni Strateji Olarak Kaydet"))
+        .css_classes(vec!["suggested-action"])
+        .sensitive(false)
+        .build();
+    header.pack_end(&save_button);
+
+    let content = Box::new(Orientation::Vertical, 20);
+    content.set_margin_top(20);
+    content.set_margin_bottom(20);
+    content.set_margin_start(20);
+    content.set_margin_end(20);
+
+    let modes_group = adw::PreferencesGroup::builder()
+        .title(t("Desync Modu"))
+        .description("--dpi-desync")
+        .build();
+    let phase0 = mode_combo(&t("Bağlantı aşaması"), nfqws::DESYNC_PHASE0_MODES, original.phase0.as_deref());
+    let phase1 = mode_combo(&t("Sahte paket"), nfqws::DESYNC_PHASE1_MODES, original.phase1.as_deref());
+    let phase2 = mode_combo(&t("Bölme / sıralama"), nfqws::DESYNC_PHASE2_MODES, original.phase2.as_deref());
+    modes_group.add(&phase0);
+    modes_group.add(&phase1);
+    modes_group.add(&phase2);
+    content.append(&modes_group);
+
+    let params_group = adw::PreferencesGroup::builder()
+        .title(t("Parametreler"))
+        .build();
+    let ttl = adw::SpinRow::with_range(0.0, 255.0, 1.0);
+    ttl.set_title("--dpi-desync-ttl");
+    ttl.set_subtitle(&t("Sahte paketlerin TTL değeri (0 = kullanılmaz)"));
+    ttl.set_value(original.ttl.unwrap_or(0) as f64);
+    params_group.add(&ttl);
+    let repeats = adw::SpinRow::with_range(0.0, 100.0, 1.0);
+    repeats.set_title("--dpi-desync-repeats");
+    repeats.set_subtitle(&t("Sahte paket tekrar sayısı (0 = varsayılan)"));
+    repeats.set_value(original.repeats.unwrap_or(0) as f64);
+    params_group.add(&repeats);
+    let split_pos = adw::EntryRow::builder()
+        .title("--dpi-desync-split-pos")
+        .text(original.split_pos.as_deref().unwrap_or(""))
+        .build();
+    split_pos.set_tooltip_text(Some(&t("Sayılar veya işaretler, virgülle ayrılmış: {}. Örnek: 1,midsld+1").replace("{}", &nfqws::SPLIT_POS_MARKERS.join(", "))));
+    params_group.add(&split_pos);
+    let fooling = adw::ExpanderRow::builder()
+        .title("--dpi-desync-fooling")
+        .subtitle(t("Sahte paketleri DPI'ya geçerli, sunucuya geçersiz gösterme yöntemleri"))
+        .expanded(!original.fooling.is_empty())
+        .build();
+    let mut fooling_switches = Vec::new();
+    for value in nfqws::FOOLING_VALUES {
+        let row = adw::SwitchRow::builder()
+            .title(*value)
+            .active(original.fooling.iter().any(|f| f == value))
+            .build();
+        fooling.add_row(&row);
+        fooling_switches.push((value.to_string(), row));
+    }
+    params_group.add(&fooling);
+    let extra = adw::EntryRow::builder()
+        .title(t("Diğer argümanlar"))
+        .text(original.extra.join(" "))
+        .build();
+    params_group.add(&extra);
+    content.append(&params_group);
+
+    let preview_group = adw::PreferencesGroup::builder()
+        .title(t("Komut Satırı"))
+        .build();
+    let preview = Label::builder()
+        .wrap(true)
+        .wrap_mode(gtk::pango::WrapMode::WordChar)
+        .selectable(true)
+        .xalign(0.0)
+        .css_classes(vec!["monospace", "card"])
+        .build();
+    preview_group.add(&preview);
+    let problems = Label::builder()
+        .wrap(true)
+        .xalign(0.0)
+        .visible(false)
+        .css_classes(vec!["error"])
+        .build();
+    preview_group.add(&problems);
+    content.append(&preview_group);
+
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+    let view = adw::ToolbarView::builder()
+        .content(&scrolled)
+        .build();
+    view.add_top_bar(&header);
+    window.set_content(Some(&view));
+
+    let collect = {
+        let phase0 = phase0.clone();
+        let phase1 = phase1.clone();
+        let phase2 = phase2.clone();
+        let ttl = ttl.clone();
+        let repeats = repeats.clone();
+        let split_pos = split_pos.clone();
+        let extra = extra.clone();
+        let fooling_switches = fooling_switches.clone();
+        let original = original.clone();
+        Rc::new(move || -> StrategyFields {
+            let split_text = split_pos.text().trim().to_string();
+            StrategyFields {
+                phase0: combo_value(&phase0, nfqws::DESYNC_PHASE0_MODES),
+                phase1: combo_value(&phase1, nfqws::DESYNC_PHASE1_MODES),
+                phase2: combo_value(&phase2, nfqws::DESYNC_PHASE2_MODES),
+                other_modes: original.other_modes.clone(),
+                ttl: Some(ttl.value() as u32).filter(|v| *v > 0),
+                fooling: fooling_switches
+                    .iter()
+                    .filter(|(_, row)| row.is_active())
+                    .map(|(value, _)| value.clone())
+                    .collect(),
+                split_pos: Some(split_text).filter(|s| !s.is_empty()),
+                repeats: Some(repeats.value() as u32).filter(|v| *v > 0),
+                extra: extra.text().split_whitespace().map(|s| s.to_string()).collect(),
+            }
+        })
+    };
+
+    let supported = lint::supported_options();
+    let refresh = {
+        let collect = collect.clone();
+        let preview = preview.clone();
+        let problems = problems.clone();
+        let save_button = save_button.clone();
+        let split_pos = split_pos.clone();
+        let original_text = original_text.clone();
+        Rc::new(move || {
+            let fields = collect();
+            let result = fields.to_strategy();
+            preview.set_label(&format!("nfqws {}", result));
+            let mut errors = Vec::new();
+            if fields.phase0.is_none() && fields.phase1.is_none() && fields.phase2.is_none() && fields.other_modes.is_empty() {
+                errors.push(t("En az bir desync modu seçin."));
+            }
+            match &fields.split_pos {
+                Some(pos) if !nfqws::is_valid_split_pos(pos) => {
+                    split_pos.add_css_class("error");
+                    errors.push(t("Geçersiz bölme konumu: '{}'").replace("{}", pos));
+                },
+                _ => split_pos.remove_css_class("error"),
+            }
+            let issues = lint::lint_strategy(&result, supported.as_deref());
+            let blocked = !errors.is_empty() || lint::has_errors(&issues);
+            if !issues.is_empty() {
+                errors.push(lint::format_issues(&issues));
+            }
+            if blocked {
+                problems.add_css_class("error");
+                problems.remove_css_class("warning");
+            } else {
+                problems.add_css_class("warning");
+                problems.remove_css_class("error");
+            }
+            problems.set_label(&errors.join("\n"));
+            problems.set_visible(!errors.is_empty());
+            save_button.set_sensitive(!blocked && result != original_text);
+        })
+    };
+    for combo in [&phase0, &phase1, &phase2] {
+        let r = refresh.clone();
+        combo.connect_selected_notify(move |_| r());
+    }
+    for spin in [&ttl, &repeats] {
+        let r = refresh.clone();
+        spin.connect_value_notify(move |_| r());
+    }
+    for entry in [&split_pos, &extra] {
+        let r = refresh.clone();
+        entry.connect_changed(move |_| r());
+    }
+    for (_, row) in &fooling_switches {
+        let r = refresh.clone();
+        row.connect_active_notify(move |_| r());
+    }
+    refresh();
+
+    let window_save = window.clone();
+    save_button.connect_clicked(move |_| {
+        on_save(collect().to_strategy());
+        window_save.close();
+    });
+    window.present();
+}
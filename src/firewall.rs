@@ -0,0 +1,109 @@
+use std::process::Command;
+use crate::{settings, t};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Backend {
+    Iptables,
+    Nftables,
+}
+
+impl Backend {
+    pub fn fwtype(self) -> &'static str {
+        match self {
+            Backend::Iptables => "iptables",
+            Backend::Nftables => "nftables",
+        }
+    }
+
+    pub fn binary_deps(self) -> &'static [&'static str] {
+        match self {
+            Backend::Iptables => &["iptables", "ipset"],
+            Backend::Nftables => &["nft"],
+        }
+    }
+}
+
+pub const OVERRIDE_CHOICES: [&str; 3] = ["auto", "iptables", "nftables"];
+
+fn command_exists(name: &str) -> bool {
+    Command::new("which")
+        .arg(name)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn distro_default(distro: &str) -> Backend {
+    match distro {
+        "alpine" | "void" | "gentoo" | "antix" | "mx" => Backend::Iptables,
+        _ => Backend::Nftables,
+    }
+}
+
+// An iptables reporting nf_tables is only the compat layer; zapret should use nft then.
+pub fn detect_backend(distro: &str) -> Backend {
+    let has_nft = command_exists("nft");
+    let iptables_version = Command::new("iptables")
+        .arg("-V")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string());
+    match (has_nft, iptables_version) {
+        (true, Some(version)) if version.contains("legacy") => Backend::Iptables,
+        (true, _) => Backend::Nftables,
+        (false, Some(_)) => Backend::Iptables,
+        (false, None) => distro_default(distro),
+    }
+}
+
+pub fn override_choice() -> String {
+    settings::get("firewall_backend")
+        .filter(|v| OVERRIDE_CHOICES.contains(&v.as_str()))
+        .unwrap_or_else(|| "auto".to_string())
+}
+
+pub fn set_override_choice(choice: &str) -> std::io::Result<()> {
+    settings::set("firewall_backend", choice)
+}
+
+pub fn resolve_backend(distro: &str) -> Backend {
+    match override_choice().as_str() {
+        "iptables" => Backend::Iptables,
+        "nftables" => Backend::Nftables,
+        _ => detect_backend(distro),
+    }
+}
+
+pub fn describe(distro: &str) -> String {
+    let detected = detect_backend(distro).fwtype();
+    match override_choice().as_str() {
+        "auto" => t("Algılanan: {}").replace("{}", detected),
+        choice => t("Elle seçildi: {} (algılanan: {})")
+            .replacen("{}", choice, 1)
+            .replacen("{}", detected, 1),
+    }
+}
+
+pub fn set_fwtype(content: &str, backend: Backend) -> String {
+    let line = format!("FWTYPE={}", backend.fwtype());
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|l| {
+            let trimmed = l.trim_start().trim_start_matches('#').trim_start();
+            if !replaced && trimmed.starts_with("FWTYPE=") {
+                replaced = true;
+                line.clone()
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(line);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
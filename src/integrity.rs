@@ -0,0 +1,145 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::{get_config_dir, settings, t};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verification {
+    Confirmed,
+    Skipped,
+}
+
+pub struct Pending {
+    pub name: String,
+    pub kind: String,
+    pub value: String,
+}
+
+pub fn enabled() -> bool {
+    settings::get_bool("zapret_verify", true)
+}
+
+pub fn set_enabled(enabled: bool) -> io::Result<()> {
+    settings::set("zapret_verify", if enabled { "true" } else { "false" })
+}
+
+pub fn get_trust_path() -> PathBuf {
+    get_config_dir().join("trusted_releases.txt")
+}
+
+fn get_pending_path() -> PathBuf {
+    get_config_dir().join("pending_release.txt")
+}
+
+fn parse_entry(line: &str) -> Option<Pending> {
+    let mut parts = line.split_whitespace();
+    Some(Pending {
+        name: parts.next()?.to_string(),
+        kind: parts.next()?.to_string(),
+        value: parts.next()?.to_string(),
+    })
+}
+
+fn confirmed() -> Vec<Pending> {
+    fs::read_to_string(get_trust_path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(parse_entry)
+        .collect()
+}
+
+pub fn pending() -> Option<Pending> {
+    parse_entry(&fs::read_to_string(get_pending_path()).ok()?)
+}
+
+pub fn trust_pending() -> io::Result<()> {
+    let Some(entry) = pending() else {
+        return Ok(());
+    };
+    let mut content = fs::read_to_string(get_trust_path()).unwrap_or_default();
+    content.push_str(&format!("{} {} {}\n", entry.name, entry.kind, entry.value));
+    fs::write(get_trust_path(), content)?;
+    fs::remove_file(get_pending_path())
+}
+
+fn failure(detail: String) -> String {
+    format!("{}\n\n{}", t("Zapret kaynağının bütünlüğü doğrulanamadı, kurulum durduruldu."), detail)
+}
+
+fn check_value(name: &str, kind: &str, value: &str) -> Result<Verification, String> {
+    let _ = fs::remove_file(get_pending_path());
+    match confirmed().into_iter().find(|e| e.name == name && e.kind == kind) {
+        Some(entry) if entry.value.eq_ignore_ascii_case(value) => Ok(Verification::Confirmed),
+        Some(entry) => Err(failure(t("{tag} için beklenen {kind} {e}, indirilen {g}. {o}")
+            .replace("{tag}", name)
+            .replace("{kind}", kind)
+            .replace("{e}", &entry.value)
+            .replace("{g}", value)
+            .replace("{o}", &t("Bu değeri daha önce siz onayladınız ({}). Değişiklik bilerek yapıldıysa satırı silin.")
+                .replace("{}", &get_trust_path().display().to_string())))),
+        None => {
+            let _ = fs::write(get_pending_path(), format!("{} {} {}\n", name, kind, value));
+            Err(failure(t("{n} henüz onaylanmadı ({k} {v}). Değeri sürüm sayfasıyla karşılaştırıp onaylamadan kurulum yapılmaz.")
+                .replace("{n}", name)
+                .replace("{k}", kind)
+                .replace("{v}", value)))
+        }
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn verify_checkout(dir: &Path, tag: Option<&str>) -> Result<Verification, String> {
+    if !enabled() {
+        return Ok(Verification::Skipped);
+    }
+    let Some(tag) = tag else {
+        return Err(failure(t("Kaynak bir sürüm etiketinden alınmadı, karşılaştırılacak bir değer yok. Bir sürüm sabitleyin veya sürüm etiketi olan bir depo kullanın.")));
+    };
+    let Some(commit) = git(dir, &["rev-parse", "HEAD"]) else {
+        return Err(failure(t("Kaynak klasöründe git bilgisi yok.")));
+    };
+    check_value(tag, "commit", &commit)
+}
+
+fn sha256(path: &Path) -> Result<String, String> {
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .filter(|_| output.status.success())
+        .map(|s| s.to_string())
+        .ok_or_else(|| t("Dosya okunamadı: {}").replace("{}", &path.display().to_string()))
+}
+
+pub fn verify_archive(path: &Path) -> Result<Verification, String> {
+    if !enabled() {
+        return Ok(Verification::Skipped);
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().replace(char::is_whitespace, "_")).unwrap_or_default();
+    check_value(&name, "sha256", &sha256(path)?)
+}
+
+pub fn verify_local(path: &Path) -> Result<Verification, String> {
+    if !enabled() {
+        return Ok(Verification::Skipped);
+    }
+    if !path.is_dir() {
+        return verify_archive(path);
+    }
+    if git(path, &["rev-parse", "HEAD"]).is_none() {
+        return Err(failure(t("Klasörde git bilgisi olmadığı için doğrulanamıyor. Bir sürüm arşivi seçin veya ayarlardan doğrulamayı kapatın.")));
+    }
+    let tag = git(path, &["describe", "--tags", "--exact-match", "HEAD"]);
+    verify_checkout(path, tag.as_deref())
+}
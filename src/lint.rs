@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use crate::nfqws::{self, parse_strategy_args};
+use crate::{get_zapret_path, t};
+
+lazy_static! {
+    static ref SUPPORTED_OPTIONS: Mutex<Option<Vec<String>>> = Mutex::new(None);
+}
+
+const DEPRECATED_OPTIONS: &[(&str, &str)] = &[
+    ("--dpi-desync-split-http-req", "--dpi-desync-split-pos"),
+    ("--dpi-desync-split-tls", "--dpi-desync-split-pos"),
+];
+const DEPRECATED_MODES: &[(&str, &str)] = &[
+    ("split", "multisplit"),
+    ("split2", "multisplit"),
+    ("disorder", "multidisorder"),
+    ("disorder2", "multidisorder"),
+];
+const REPEATABLE_OPTIONS: &[&str] = &["--hostlist", "--hostlist-exclude", "--ipset", "--ipset-exclude", "--dpi-desync-fake-tls", "--dpi-desync-fake-tls-mod"];
+const VALUE_OPTIONS: &[&str] = &["--dpi-desync", "--dpi-desync-ttl", "--dpi-desync-ttl6", "--dpi-desync-repeats", "--dpi-desync-fooling", "--dpi-desync-split-pos", "--filter-tcp", "--filter-udp"];
+
+#[derive(PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+pub struct LintIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn error(message: String) -> LintIssue {
+    LintIssue { severity: Severity::Error, message }
+}
+
+fn warning(message: String) -> LintIssue {
+    LintIssue { severity: Severity::Warning, message }
+}
+
+pub fn get_nfqws_binary() -> Option<PathBuf> {
+    [PathBuf::from("/opt/zapret/nfq/nfqws"), get_zapret_path().join("nfq").join("nfqws")]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+fn parse_help_options(help: &str) -> Vec<String> {
+    let mut options = Vec::new();
+    for token in help.split(|c: char| c.is_whitespace() || c == '=' || c == '[' || c == ',' || c == ';' || c == ':') {
+        if token.starts_with("--") && token.len() > 2 {
+            let name: String = token.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+            if name.len() > 2 && !options.contains(&name) {
+                options.push(name);
+            }
+        }
+    }
+    options
+}
+
+fn read_supported_options(binary: &Path) -> Option<Vec<String>> {
+    let output = Command::new(binary).arg("--help").output().ok()?;
+    let mut help = String::from_utf8_lossy(&output.stdout).to_string();
+    help.push_str(&String::from_utf8_lossy(&output.stderr));
+    let options = parse_help_options(&help);
+    if options.is_empty() { None } else { Some(options) }
+}
+
+pub fn supported_options() -> Option<Vec<String>> {
+    if let Ok(guard) = SUPPORTED_OPTIONS.lock() {
+        if guard.is_some() {
+            return guard.clone();
+        }
+    }
+    let options = read_supported_options(&get_nfqws_binary()?)?;
+    if let Ok(mut guard) = SUPPORTED_OPTIONS.lock() {
+        *guard = Some(options.clone());
+    }
+    Some(options)
+}
+
+fn is_valid_port_list(value: &str) -> bool {
+    !value.is_empty() && value.split(',').all(|range| {
+        let mut bounds = range.splitn(2, '-');
+        bounds.all(|b| b.parse::<u32>().map(|p| p <= 65535).unwrap_or(false))
+    })
+}
+
+fn is_valid_autottl(value: &str) -> bool {
+    let (delta, range) = value.split_once(':').unwrap_or((value, ""));
+    let delta_ok = delta.trim_start_matches('-').parse::<u32>().is_ok();
+    let range_ok = range.is_empty() || range.split('-').all(|v| v.parse::<u32>().map(|n| n <= 255).unwrap_or(false));
+    delta_ok && range_ok
+}
+
+pub fn lint_strategy(strategy: &str, supported: Option<&[String]>) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let args = parse_strategy_args(strategy);
+    if args.is_empty() {
+        issues.push(error(t("Strateji boş.")));
+        return issues;
+    }
+    let mut seen: Vec<String> = Vec::new();
+    for (name, value) in &args {
+        if !name.starts_with("--") {
+            issues.push(error(t("Beklenmeyen argüman: '{}'").replace("{}", name)));
+            continue;
+        }
+        if let Some(options) = supported {
+            if !options.contains(name) {
+                issues.push(error(t("Kurulu nfqws bu seçeneği desteklemiyor: {}").replace("{}", name)));
+            }
+        }
+        if let Some((_, replacement)) = DEPRECATED_OPTIONS.iter().find(|(old, _)| old == name) {
+            issues.push(warning(t("{o} artık kullanılmıyor, yerine {n} kullanın.").replace("{o}", name).replace("{n}", replacement)));
+        }
+        if seen.contains(name) {
+            if name == "--dpi-desync" {
+                issues.push(error(t("Birden fazla --dpi-desync seçeneği var, yalnızca sonuncusu geçerli olur.")));
+            } else if !REPEATABLE_OPTIONS.contains(&name.as_str()) {
+                issues.push(warning(t("Seçenek birden fazla kez kullanılmış: {}").replace("{}", name)));
+            }
+        } else {
+            seen.push(name.clone());
+        }
+        let value = match value {
+            Some(v) => v.as_str(),
+            None => {
+                if VALUE_OPTIONS.contains(&name.as_str()) {
+                    issues.push(error(t("{} için değer eksik.").replace("{}", name)));
+                }
+                continue;
+            }
+        };
+        let malformed = match name.as_str() {
+            "--dpi-desync" => {
+                lint_desync_modes(value, &mut issues);
+                false
+            },
+            "--dpi-desync-ttl" | "--dpi-desync-ttl6" => value.parse::<u32>().map(|v| v > 255).unwrap_or(true),
+            "--dpi-desync-repeats" => value.parse::<u32>().map(|v| v == 0).unwrap_or(true),
+            "--dpi-desync-fooling" => value.split(',').any(|f| !nfqws::FOOLING_VALUES.contains(&f)),
+            "--dpi-desync-split-pos" => !nfqws::is_valid_split_pos(value),
+            "--dpi-desync-autottl" | "--dpi-desync-autottl6" => !is_valid_autottl(value),
+            "--filter-tcp" | "--filter-udp" => !is_valid_port_list(value),
+            _ => false,
+        };
+        if malformed {
+            issues.push(error(t("Geçersiz değer: {n}={v}").replace("{n}", name).replace("{v}", value)));
+        }
+    }
+    issues
+}
+
+fn lint_desync_modes(value: &str, issues: &mut Vec<LintIssue>) {
+    let modes: Vec<&str> = value.split(',').collect();
+    let mut phases = [0; 3];
+    for mode in &modes {
+        if nfqws::DESYNC_PHASE0_MODES.contains(mode) {
+            phases[0] += 1;
+        } else if nfqws::DESYNC_PHASE1_MODES.contains(mode) {
+            phases[1] += 1;
+        } else if nfqws::DESYNC_PHASE2_MODES.contains(mode) {
+            phases[2] += 1;
+        } else {
+            issues.push(error(t("Bilinmeyen desync modu: {}").replace("{}", mode)));
+        }
+        if let Some((_, replacement)) = DEPRECATED_MODES.iter().find(|(old, _)| old == mode) {
+            issues.push(warning(t("{o} modu eskidi, yerine {n} kullanın.").replace("{o}", mode).replace("{n}", replacement)));
+        }
+    }
+    if phases.iter().any(|&count| count > 1) {
+        issues.push(error(t("Çakışan desync modları: {}. Her aşamadan yalnızca bir mod seçilebilir.").replace("{}", value)));
+    }
+}
+
+pub fn has_errors(issues: &[LintIssue]) -> bool {
+    issues.iter().any(|i| i.severity == Severity::Error)
+}
+
+pub fn format_issues(issues: &[LintIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| match i.severity {
+            Severity::Error => format!("✖ {}", i.message),
+            Severity::Warning => format!("⚠ {}", i.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -14,16 +14,37 @@ use std::path::{Path, PathBuf};
 use std::io::{self, Write, BufRead, BufReader};
 use std::env;
 use std::rc::Rc;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use directories::ProjectDirs;
 use gtk::gdk;
 use gettext::Catalog;
 use lazy_static::lazy_static;
 use sys_locale::get_locale;
 use std::io::Cursor;
+mod benchmark;
+mod conflicts;
+mod dbus_service;
+mod dns;
+mod dnscheck;
+mod domains;
+mod editor;
+mod integrity;
+mod firewall;
+mod lint;
+mod monitor;
+mod nfqws;
+mod notify;
+mod presets;
+mod profiles;
+mod rules;
+mod settings;
+mod source;
+mod tray;
+mod version;
 const EN_MO: &[u8] = include_bytes!("../locale/en_US/LC_MESSAGES/zapret-gtk.mo");
 const RU_MO: &[u8] = include_bytes!("../locale/ru_RU/LC_MESSAGES/zapret-gtk.mo");
 const ICON_BYTES: &[u8] = include_bytes!("../zapretgtk512.png");
+static EXPECTED_STOP: AtomicBool = AtomicBool::new(false);
 lazy_static! {
     static ref CATALOG: Mutex<Option<Catalog>> = Mutex::new(None);
 }
@@ -35,28 +56,24 @@ fn t(s: &str) -> String {
     }
     s.to_string()
 }
-fn get_config_path() -> PathBuf {
+fn get_config_dir() -> PathBuf {
     if let Some(proj_dirs) = ProjectDirs::from("com", "Taygun86", "zapret-gtk") {
         let config_dir = proj_dirs.config_dir();
         if !config_dir.exists() {
             let _ = fs::create_dir_all(config_dir);
         }
-        config_dir.join("strategies.json")
+        config_dir.to_path_buf()
     } else {
-        PathBuf::from("strategies.json")
+        PathBuf::from(".")
     }
 }
 
+fn get_config_path() -> PathBuf {
+    get_config_dir().join("strategies.json")
+}
+
 fn get_log_path() -> PathBuf {
-    if let Some(proj_dirs) = ProjectDirs::from("com", "Taygun86", "zapret-gtk") {
-        let config_dir = proj_dirs.config_dir();
-        if !config_dir.exists() {
-            let _ = fs::create_dir_all(config_dir);
-        }
-        config_dir.join("log.txt")
-    } else {
-        PathBuf::from("log.txt")
-    }
+    get_config_dir().join("log.txt")
 }
 
 fn rotate_logs() {
@@ -138,6 +155,10 @@ fn delete_local_zapret_folder() {
     });
 }
 fn build_ui(app: &Application) {
+    if let Some(window) = app.windows().first() {
+        window.present();
+        return;
+    }
     let nav_view = NavigationView::new();
     let content_box1 = Box::new(Orientation::Vertical, 0);
     let top_box1 = Box::new(Orientation::Vertical, 0);
@@ -168,9 +189,17 @@ fn build_ui(app: &Application) {
         .wrap(true)
         .max_width_chars(40)
         .justify(gtk::Justification::Center)
-        .visible(true)
+        .visible(false)
         .build();
     top_box1.append(&dns_warning_label);
+    let dns_problem = Rc::new(Cell::new(false));
+    let problems = dnscheck::last_problems();
+    if !problems.is_empty() {
+        let text = format!("{}\n{}", t("UYARI: Varsayılan servis sağlayıcı DNS'i ile çalışmaz. Lütfen Cloudflare veya alternatif bir DNS kullanın."), problems.join("\n"));
+        dns_warning_label.set_markup(&format!("<span foreground='red' weight='bold'>{}</span>", glib::markup_escape_text(&text)));
+        dns_problem.set(true);
+        dns_warning_label.set_visible(placeholder_label.is_visible());
+    }
     let progress_bar = ProgressBar::builder()
         .margin_top(10)
         .margin_bottom(10)
@@ -194,6 +223,122 @@ fn build_ui(app: &Application) {
     let header1 = HeaderBar::builder()
         .show_end_title_buttons(true)
         .build();
+    let source_box = Box::new(Orientation::Vertical, 5);
+    source_box.set_margin_top(5);
+    source_box.set_margin_bottom(5);
+    source_box.set_margin_start(5);
+    source_box.set_margin_end(5);
+    let source_label = Label::builder()
+        .label(&source::describe())
+        .wrap(true)
+        .max_width_chars(35)
+        .xalign(0.0)
+        .css_classes(vec!["dim-label"])
+        .build();
+    source_box.append(&source_label);
+    let source_archive_btn = Button::builder()
+        .label(&t("Yerel Arşiv Seç..."))
+        .css_classes(vec!["flat"])
+        .build();
+    let source_folder_btn = Button::builder()
+        .label(&t("Yerel Klasör Seç..."))
+        .css_classes(vec!["flat"])
+        .build();
+    let source_remote_btn = Button::builder()
+        .label(&t("Uzak Depodan İndir"))
+        .css_classes(vec!["flat"])
+        .build();
+    source_box.append(&source_archive_btn);
+    source_box.append(&source_folder_btn);
+    source_box.append(&source_remote_btn);
+    let prebuilt_check = CheckButton::builder()
+        .label(&t("Önceden derlenmiş ikili dosyaları kullan"))
+        .active(source::prebuilt_mode())
+        .build();
+    prebuilt_check.set_tooltip_text(Some(&t("Derleme araçları kurulmaz; install_bin.sh sisteme uygun ikili dosyaları seçer. Uygun ikili yoksa derlemeye dönülür.")));
+    prebuilt_check.connect_toggled(|check| {
+        if let Err(e) = source::set_prebuilt_mode(check.is_active()) {
+            log_to_file(&format!("Could not save install mode: {}", e));
+        }
+    });
+    source_box.append(&prebuilt_check);
+    let source_popover = gtk::Popover::builder()
+        .child(&source_box)
+        .build();
+    let source_button = gtk::MenuButton::builder()
+        .icon_name("folder-download-symbolic")
+        .tooltip_text(&t("Kurulum Kaynağı"))
+        .popover(&source_popover)
+        .build();
+    header1.pack_start(&source_button);
+    let source_label_show = source_label.clone();
+    source_popover.connect_show(move |_| source_label_show.set_label(&source::describe()));
+    let use_local_source = {
+        let source_label = source_label.clone();
+        move |anchor: &Button, path: PathBuf| {
+            let result = source::verify(&path).and_then(|()| source::set_local_source(Some(&path)).map_err(|e| e.to_string()));
+            source_label.set_label(&source::describe());
+            if let Err(e) = result {
+                let dialog = adw::MessageDialog::builder()
+                    .heading(&t("Geçersiz Kaynak"))
+                    .body(&e)
+                    .build();
+                if let Some(win) = anchor.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+                    dialog.set_transient_for(Some(&win));
+                }
+                dialog.add_response("ok", &t("Tamam"));
+                dialog.present();
+            }
+        }
+    };
+    let use_archive = use_local_source.clone();
+    let popover_archive = source_popover.clone();
+    source_archive_btn.connect_clicked(move |btn| {
+        popover_archive.popdown();
+        let filter = FileFilter::new();
+        filter.set_name(Some(&t("zapret arşivleri")));
+        for pattern in ["*.tar.gz", "*.tgz", "*.tar.xz", "*.tar.bz2", "*.tar", "*.zip"] {
+            filter.add_pattern(pattern);
+        }
+        let filters = gtk::gio::ListStore::new::<FileFilter>();
+        filters.append(&filter);
+        let file_dialog = gtk::FileDialog::builder()
+            .title(&t("Yerel Arşiv Seç..."))
+            .filters(&filters)
+            .modal(true)
+            .build();
+        let win = btn.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+        let use_archive = use_archive.clone();
+        let anchor = btn.clone();
+        file_dialog.open(win.as_ref(), None::<&gtk::gio::Cancellable>, move |result| {
+            if let Some(path) = result.ok().and_then(|file| file.path()) {
+                use_archive(&anchor, path);
+            }
+        });
+    });
+    let popover_folder = source_popover.clone();
+    source_folder_btn.connect_clicked(move |btn| {
+        popover_folder.popdown();
+        let file_dialog = gtk::FileDialog::builder()
+            .title(&t("Yerel Klasör Seç..."))
+            .modal(true)
+            .build();
+        let win = btn.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+        let use_folder = use_local_source.clone();
+        let anchor = btn.clone();
+        file_dialog.select_folder(win.as_ref(), None::<&gtk::gio::Cancellable>, move |result| {
+            if let Some(path) = result.ok().and_then(|file| file.path()) {
+                use_folder(&anchor, path);
+            }
+        });
+    });
+    source_remote_btn.connect_clicked(move |_| {
+        if let Err(e) = source::set_local_source(None) {
+            log_to_file(&format!("Could not reset zapret source: {}", e));
+        }
+        source_label.set_label(&source::describe());
+        source_popover.popdown();
+    });
     let view1 = ToolbarView::builder()
         .content(&content_box1)
         .build();
@@ -231,6 +376,21 @@ fn build_ui(app: &Application) {
         .max_width_chars(40)
         .build();
     content_box_check.append(&conflict_list_label);
+    let conflict_list_box = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(vec!["boxed-list"])
+        .build();
+    let conflict_scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(320)
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(20)
+        .visible(false)
+        .child(&conflict_list_box)
+        .build();
+    content_box_check.append(&conflict_scrolled);
     let force_continue_button = Button::builder()
         .label(&t("Yine de Devam Et"))
         .visible(false)
@@ -311,7 +471,7 @@ fn build_ui(app: &Application) {
     let top_box2 = Box::new(Orientation::Vertical, 0);
     top_box2.set_vexpand(true);
     let info_label = Label::builder()
-        .label(&t("Erişemediğiniz web sitelerinin alan adlarını, her satıra bir tane gelecek şekilde yazın. Başlarına 'https://' ve 'www.' eklemeyin. Örnek: (a.com), (b.net)"))
+        .label(&t("Erişemediğiniz web sitelerinin alan adlarını, her satıra bir tane gelecek şekilde yazın veya bir liste yapıştırın. Adresler (https://, www., yol ve port) otomatik olarak alan adına dönüştürülür. Örnek: (a.com), (b.net)"))
         .margin_top(15)
         .margin_bottom(10)
         .wrap(true)
@@ -344,7 +504,17 @@ fn build_ui(app: &Application) {
     add_button.connect_clicked(move |_| {
         add_entry_row(&entries_container_clone, true);
     });
-    top_box2.append(&add_button);
+    let presets_button = Button::builder()
+        .icon_name("view-list-symbolic")
+        .tooltip_text(&t("Hazır listelerden ekle"))
+        .css_classes(vec!["flat"])
+        .margin_bottom(10)
+        .build();
+    let entry_buttons_box = Box::new(Orientation::Horizontal, 10);
+    entry_buttons_box.set_halign(gtk::Align::Center);
+    entry_buttons_box.append(&add_button);
+    entry_buttons_box.append(&presets_button);
+    top_box2.append(&entry_buttons_box);
     content_box2.append(&top_box2);
     let bottom_box2 = Box::new(Orientation::Vertical, 0);
     let action_buttons_box = Box::new(Orientation::Horizontal, 10);
@@ -438,6 +608,33 @@ fn build_ui(app: &Application) {
         .tooltip_text(&t("Ayarlar"))
         .build();
     mgmt_buttons_box.append(&settings_mgmt_btn);
+    let benchmark_button = Button::builder()
+        .icon_name("speedometer-symbolic")
+        .css_classes(vec!["pill"])
+        .tooltip_text(&t("Hız Testi"))
+        .build();
+    mgmt_buttons_box.append(&benchmark_button);
+    let rules_button = Button::builder()
+        .icon_name("system-search-symbolic")
+        .css_classes(vec!["pill"])
+        .tooltip_text(&t("Güvenlik Duvarı Kuralları"))
+        .build();
+    mgmt_buttons_box.append(&rules_button);
+    let profiles_box = Box::new(Orientation::Vertical, 5);
+    profiles_box.set_margin_top(5);
+    profiles_box.set_margin_bottom(5);
+    profiles_box.set_margin_start(5);
+    profiles_box.set_margin_end(5);
+    let profiles_popover = gtk::Popover::builder()
+        .child(&profiles_box)
+        .build();
+    let profiles_button = gtk::MenuButton::builder()
+        .icon_name("view-list-bullet-symbolic")
+        .css_classes(vec!["pill"])
+        .tooltip_text(&t("Profiller"))
+        .popover(&profiles_popover)
+        .build();
+    mgmt_buttons_box.append(&profiles_button);
     let apply_button = Button::builder()
         .label(&t("Uygula"))
         .css_classes(vec!["suggested-action", "pill"])
@@ -453,8 +650,13 @@ fn build_ui(app: &Application) {
     let header_status = HeaderBar::builder()
         .show_back_button(true)
         .build();
+    let scrolled_status = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content_box_status)
+        .build();
     let view_status = ToolbarView::builder()
-        .content(&content_box_status)
+        .content(&scrolled_status)
         .build();
     view_status.add_top_bar(&header_status);
     let status_box = Box::new(Orientation::Vertical, 10);
@@ -499,6 +701,147 @@ fn build_ui(app: &Application) {
     status_row.append(&service_buttons_box);
     status_box.append(&status_row);
     content_box_status.append(&status_box);
+    let monitor_group = adw::PreferencesGroup::builder()
+        .title(&t("Bağlantı İzleyici"))
+        .description(&t("Kayıtlı alan adlarına Zapret üzerinden düzenli aralıklarla erişmeyi dener ve erişim oranı düştüğünde uyarır."))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let monitor_switch = adw::SwitchRow::builder()
+        .title(&t("Arka Planda İzle"))
+        .active(monitor::is_enabled())
+        .build();
+    monitor_group.add(&monitor_switch);
+    let monitor_interval = adw::SpinRow::with_range(5.0, 1440.0, 5.0);
+    monitor_interval.set_title(&t("Kontrol Aralığı (dakika)"));
+    monitor_interval.set_value(monitor::interval_minutes() as f64);
+    monitor_group.add(&monitor_interval);
+    let monitor_threshold = adw::SpinRow::with_range(10.0, 100.0, 5.0);
+    monitor_threshold.set_title(&t("Uyarı Eşiği (%)"));
+    monitor_threshold.set_subtitle(&t("Erişim oranı bu değerin altına düşünce uyarı gösterilir"));
+    monitor_threshold.set_value(monitor::threshold() as f64);
+    monitor_group.add(&monitor_threshold);
+    let monitor_stats_row = adw::ActionRow::builder()
+        .title(&t("Son Durum"))
+        .subtitle(&monitor::summary())
+        .build();
+    monitor_group.add(&monitor_stats_row);
+    monitor_switch.connect_active_notify(|row| {
+        if let Err(e) = settings::set("monitor_enabled", if row.is_active() { "true" } else { "false" }) {
+            log_to_file(&format!("Could not save monitor setting: {}", e));
+        }
+    });
+    monitor_interval.connect_value_notify(|row| {
+        let _ = settings::set("monitor_interval", &(row.value() as u32).to_string());
+    });
+    monitor_threshold.connect_value_notify(|row| {
+        let _ = settings::set("monitor_threshold", &(row.value() as u32).to_string());
+    });
+    content_box_status.append(&monitor_group);
+    let background_group = adw::PreferencesGroup::builder()
+        .title(&t("Arka Plan Modu"))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let background_switch = adw::SwitchRow::builder()
+        .title(&t("Arka Planda Çalış"))
+        .subtitle(&t("Pencere kapatıldığında uygulama sistem tepsisinde çalışmaya devam eder"))
+        .active(settings::get_bool("background_mode", false))
+        .build();
+    background_group.add(&background_switch);
+    content_box_status.append(&background_group);
+    let firewall_group = adw::PreferencesGroup::builder()
+        .title(&t("Güvenlik Duvarı"))
+        .description(&t("Zapret kurallarını yazacağı arka uç. Değişiklik bir sonraki uygulama veya kurulumda config dosyasına (FWTYPE) yazılır."))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let firewall_model = gtk::StringList::new(&[&t("Otomatik"), "iptables", "nftables"]);
+    let firewall_row = adw::ComboRow::builder()
+        .title(&t("Arka Uç"))
+        .subtitle(&firewall::describe(&get_distro_id()))
+        .model(&firewall_model)
+        .build();
+    let current_choice = firewall::override_choice();
+    if let Some(index) = firewall::OVERRIDE_CHOICES.iter().position(|c| *c == current_choice) {
+        firewall_row.set_selected(index as u32);
+    }
+    firewall_row.connect_selected_notify(|row| {
+        let choice = firewall::OVERRIDE_CHOICES.get(row.selected() as usize).copied().unwrap_or("auto");
+        if let Err(e) = firewall::set_override_choice(choice) {
+            log_to_file(&format!("Could not save firewall setting: {}", e));
+        }
+        row.set_subtitle(&firewall::describe(&get_distro_id()));
+    });
+    firewall_group.add(&firewall_row);
+    content_box_status.append(&firewall_group);
+    let dns_group = adw::PreferencesGroup::builder()
+        .title(&t("DNS"))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let dns_row = adw::ActionRow::builder()
+        .title(&t("DNS Ayarları"))
+        .subtitle(&dns::load().describe())
+        .activatable(true)
+        .build();
+    dns_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    dns_group.add(&dns_row);
+    content_box_status.append(&dns_group);
+    content_box_status.append(&build_version_group());
+    let remediation_group = adw::PreferencesGroup::builder()
+        .title(&t("Çakışma Düzeltmeleri"))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let remediation_row = adw::ActionRow::builder()
+        .title(&t("Yapılan Değişiklikler"))
+        .subtitle_lines(0)
+        .build();
+    let remediation_undo_btn = Button::builder()
+        .label(&t("Geri Al"))
+        .valign(gtk::Align::Center)
+        .build();
+    remediation_row.add_suffix(&remediation_undo_btn);
+    refresh_remediation_row(&remediation_row, &remediation_undo_btn);
+    remediation_group.add(&remediation_row);
+    content_box_status.append(&remediation_group);
+    let remediation_row_undo = remediation_row.clone();
+    remediation_undo_btn.connect_clicked(move |btn| {
+        btn.set_sensitive(false);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(conflicts::undo_all());
+        });
+        let row = remediation_row_undo.clone();
+        let btn_timer = btn.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    refresh_remediation_row(&row, &btn_timer);
+                    if let Err(e) = result {
+                        let dialog = adw::MessageDialog::builder()
+                            .heading(&t("Hata"))
+                            .body(&e.to_string())
+                            .build();
+                        if let Some(win) = btn_timer.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+                            dialog.set_transient_for(Some(&win));
+                        }
+                        dialog.add_response("ok", &t("Tamam"));
+                        dialog.present();
+                    }
+                    glib::ControlFlow::Break
+                },
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
     let export_box = Box::new(Orientation::Vertical, 10);
     export_box.set_margin_top(10);
     export_box.set_margin_bottom(20);
@@ -624,35 +967,41 @@ fn build_ui(app: &Application) {
     let status_label_mgmt_timer = status_label_mgmt.clone();
     let start_btn_timer = start_service_btn.clone();
     let stop_btn_timer = stop_service_btn.clone();
-    glib::timeout_add_local(Duration::from_secs(10), move || {
-        let init_sys = get_init_system();
-        let mut is_active = false;
-        let mut status_text = String::from("unknown");
-        if init_sys == "systemd" {
-            if let Ok(o) = Command::new("systemctl").arg("is-active").arg("zapret").output() {
-                status_text = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                if status_text == "active" { is_active = true; }
-            }
-        } else if init_sys == "openrc" {
-            if let Ok(o) = Command::new("rc-service").arg("zapret").arg("status").output() {
-                if o.status.success() { 
-                    is_active = true; 
-                    status_text = "active".to_string();
-                } else {
-                    status_text = "stopped".to_string();
-                }
-            }
-        } else if init_sys == "runit" {
-            if let Ok(o) = Command::new("sv").arg("status").arg("zapret").output() {
-                let out = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                if out.starts_with("run:") { 
-                    is_active = true; 
-                    status_text = "active".to_string();
-                } else {
-                    status_text = out;
-                }
+    let tray_holder: Rc<RefCell<Option<tray::Tray>>> = Rc::new(RefCell::new(None));
+    let tray_timer = tray_holder.clone();
+    let service_object = app.dbus_connection().and_then(|connection| {
+        match dbus_service::ServiceObject::register(&connection) {
+            Ok(object) => Some(Rc::new(object)),
+            Err(e) => {
+                log_to_file(&format!("Could not register D-Bus service object: {}", e));
+                None
             }
         }
+    });
+    let service_object_timer = service_object.clone();
+    let status_action = gtk::gio::SimpleAction::new_stateful("status", None, &"unknown".to_variant());
+    status_action.connect_activate(|_, _| {
+        let (is_active, status_text) = get_service_status();
+        notify::send("status", "Zapret GTK", &service_status_summary(is_active, &status_text), false, &[]);
+    });
+    app.add_action(&status_action);
+    let status_action_timer = status_action.clone();
+    let last_service_state = Rc::new(Cell::new(None::<bool>));
+    glib::timeout_add_local(Duration::from_secs(10), move || {
+        let (is_active, status_text) = get_service_status();
+        if is_active {
+            EXPECTED_STOP.store(false, Ordering::Relaxed);
+        } else if last_service_state.get() == Some(true) && !EXPECTED_STOP.load(Ordering::Relaxed) {
+            log_to_file(&format!("Zapret service stopped unexpectedly ({}).", status_text));
+            notify::send(
+                "service-stopped",
+                &t("Zapret Durdu"),
+                &t("Zapret servisi beklenmedik şekilde durdu. DPI atlatma şu anda devre dışı."),
+                true,
+                &[(t("Servisi Yeniden Başlat"), notify::ACTION_RESTART), (t("Kayıtları Aç"), notify::ACTION_OPEN_LOGS)],
+            );
+        }
+        last_service_state.set(Some(is_active));
         if is_active {
             status_label_mgmt_timer.set_label(&t("Çalışıyor (Active)"));
             status_label_mgmt_timer.add_css_class("success");
@@ -666,6 +1015,13 @@ fn build_ui(app: &Application) {
             start_btn_timer.set_visible(true);
             stop_btn_timer.set_visible(false);
         }
+        status_action_timer.set_state(&status_text.to_variant());
+        if let Some(object) = &service_object_timer {
+            object.update(is_active, &status_text, profiles::profile_names(), profiles::active_profile());
+        }
+        if let Some(tray) = tray_timer.borrow().as_ref() {
+            tray.update(is_active, &service_status_summary(is_active, &status_text), profiles::profile_names(), profiles::active_profile());
+        }
         glib::ControlFlow::Continue
     });
     let page_mgmt = NavigationPage::builder()
@@ -678,9 +1034,17 @@ fn build_ui(app: &Application) {
         .title(&t("Ayarlar"))
         .tag("status_page")
         .build();
+    let page_dns = build_dns_page(&dns_row);
+    let nav_view_for_dns = nav_view.clone();
+    dns_row.connect_activated(move |_| {
+        nav_view_for_dns.push(&page_dns);
+    });
     let nav_view_for_settings = nav_view.clone();
     let page_status_clone = page_status.clone();
+    let remediation_row_refresh = remediation_row.clone();
+    let remediation_undo_refresh = remediation_undo_btn.clone();
     settings_mgmt_btn.connect_clicked(move |_| {
+        refresh_remediation_row(&remediation_row_refresh, &remediation_undo_refresh);
         nav_view_for_settings.push(&page_status_clone);
     });
     let window = ApplicationWindow::builder()
@@ -691,24 +1055,102 @@ fn build_ui(app: &Application) {
         .content(&nav_view)
         .build();
     start_service_btn.connect_clicked(move |_| {
-         let init = get_init_system();
-         if init == "openrc" {
-             let _ = Command::new("pkexec").arg("rc-service").arg("zapret").arg("start").spawn();
-         } else if init == "runit" {
-             let _ = Command::new("pkexec").arg("sv").arg("up").arg("zapret").spawn();
-         } else {
-             let _ = Command::new("pkexec").arg("systemctl").arg("start").arg("zapret").spawn();
-         }
+        control_service("start");
     });
     stop_service_btn.connect_clicked(move |_| {
-         let init = get_init_system();
-         if init == "openrc" {
-             let _ = Command::new("pkexec").arg("rc-service").arg("zapret").arg("stop").spawn();
-         } else if init == "runit" {
-             let _ = Command::new("pkexec").arg("sv").arg("down").arg("zapret").spawn();
-         } else {
-             let _ = Command::new("pkexec").arg("systemctl").arg("stop").arg("zapret").spawn();
-         }
+        control_service("stop");
+    });
+    let restart_action = gtk::gio::SimpleAction::new("restart", None);
+    restart_action.connect_activate(|_, _| {
+        control_service("restart");
+    });
+    app.add_action(&restart_action);
+    let open_logs_action = gtk::gio::SimpleAction::new("open-logs", None);
+    open_logs_action.connect_activate(|_, _| {
+        let _ = Command::new("xdg-open")
+            .arg(get_log_path())
+            .spawn();
+    });
+    app.add_action(&open_logs_action);
+    let show_window_action = gtk::gio::SimpleAction::new("show-window", None);
+    let win_show = window.clone();
+    show_window_action.connect_activate(move |_, _| {
+        win_show.present();
+    });
+    app.add_action(&show_window_action);
+    let start_action = gtk::gio::SimpleAction::new("start", None);
+    start_action.connect_activate(|_, _| {
+        control_service("start");
+    });
+    app.add_action(&start_action);
+    let stop_action = gtk::gio::SimpleAction::new("stop", None);
+    stop_action.connect_activate(|_, _| {
+        control_service("stop");
+    });
+    app.add_action(&stop_action);
+    let apply_profile_action = gtk::gio::SimpleAction::new("apply-profile", Some(glib::VariantTy::STRING));
+    let list_apply_profile = strategies_list_box.clone();
+    apply_profile_action.connect_activate(move |_, parameter| {
+        let Some(name) = parameter.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        match apply_profile(&name) {
+            Ok(note) => {
+                if let Some(note) = note {
+                    notify::send("apply", &t("Stratejiler Uygulandı"), &note, false, &[]);
+                }
+                if let Some(profile) = profiles::find_profile(&name) {
+                    set_selected_strategies(&list_apply_profile, &profile.strategies);
+                }
+            },
+            Err((heading, body)) => notify::send("apply", &heading, &body, true, &[]),
+        }
+    });
+    app.add_action(&apply_profile_action);
+    // Set by the monitor's rescan so the failing subset does not replace domains.txt.
+    let rescan_only = Rc::new(Cell::new(false));
+    let run_blockcheck_action = gtk::gio::SimpleAction::new("run-blockcheck", None);
+    let rescan_only_blockcheck = rescan_only.clone();
+    let win_blockcheck = window.clone();
+    let nav_blockcheck = nav_view.clone();
+    let page2_blockcheck = page2.clone();
+    let entries_blockcheck = entries_container.clone();
+    let finish_blockcheck = finish_button.clone();
+    run_blockcheck_action.connect_activate(move |_, _| {
+        win_blockcheck.present();
+        let saved = domains::load_domains();
+        if !saved.is_empty() {
+            rescan_only_blockcheck.set(false);
+            replace_domain_entries(&entries_blockcheck, &saved);
+        }
+        show_domain_page(&nav_blockcheck, &page2_blockcheck);
+        if !saved.is_empty() {
+            finish_blockcheck.emit_clicked();
+        }
+    });
+    app.add_action(&run_blockcheck_action);
+    window.connect_close_request(|win| {
+        if settings::get_bool("background_mode", false) {
+            win.set_visible(false);
+            return glib::Propagation::Stop;
+        }
+        glib::Propagation::Proceed
+    });
+    if settings::get_bool("background_mode", false) {
+        start_tray(app, &window, &tray_holder, &strategies_list_box);
+    }
+    let app_background = app.clone();
+    let win_background = window.clone();
+    let list_background = strategies_list_box.clone();
+    background_switch.connect_active_notify(move |row| {
+        if let Err(e) = settings::set("background_mode", if row.is_active() { "true" } else { "false" }) {
+            log_to_file(&format!("Could not save background mode setting: {}", e));
+        }
+        if row.is_active() {
+            start_tray(&app_background, &win_background, &tray_holder, &list_background);
+        } else if let Some(tray) = tray_holder.borrow_mut().take() {
+            tray.stop();
+        }
     });
     let win_about = window.clone();
     about_btn.connect_clicked(move |_| {
@@ -738,6 +1180,8 @@ fn build_ui(app: &Application) {
     let page_test_clone = page_test.clone();
     let status_label_check_clone = status_label_check.clone();
     let conflict_list_label_clone = conflict_list_label.clone();
+    let conflict_list_box_clone = conflict_list_box.clone();
+    let conflict_scrolled_clone = conflict_scrolled.clone();
     let force_continue_button_clone = force_continue_button.clone();
     let spinner_check_clone = spinner_check.clone();
     let nav_view_clone_for_check = nav_view.clone();
@@ -786,9 +1230,11 @@ fn build_ui(app: &Application) {
         dialog.connect_response(None, move |d, response| {
             if response == "delete" {
                  log_to_file("User initiated Zapret deletion.");
+                 EXPECTED_STOP.store(true, Ordering::Relaxed);
                  let init = get_init_system();
                  let mut cmd = String::new();
                  
+                 cmd.push_str(&dns::restore_commands());
                  cmd.push_str("if [ -f /opt/zapret/uninstall_easy.sh ]; then sh /opt/zapret/uninstall_easy.sh; fi; ");
                  
                  cmd.push_str("rm -rf /opt/zapret; ");
@@ -884,24 +1330,11 @@ fn build_ui(app: &Application) {
                                 let config_content = fs::read_to_string("/opt/zapret/config")
                                     .unwrap_or_else(|_| "".to_string());
 
+                                let scores = benchmark::load_scores();
                                 for strat in strategies {
                                     let is_active = !config_content.is_empty() && config_content.contains(&strat);
 
-                                    let child_label = Label::builder()
-                                        .label(&strat)
-                                        .wrap(true)
-                                        .max_width_chars(50)
-                                        .xalign(0.0)
-                                        .build();
-                                    let check = CheckButton::builder()
-                                        .child(&child_label)
-                                        .active(is_active)
-                                        .margin_top(10)
-                                        .margin_bottom(10)
-                                        .margin_start(10)
-                                        .margin_end(10)
-                                        .build();
-                                    strategies_list_box_status_c.append(&check);
+                                    append_strategy_row(&strategies_list_box_status_c, &strat, is_active, &scores);
                                 }
                                 let dialog = adw::MessageDialog::builder()
                                     .transient_for(&win_import_status_closure)
@@ -984,128 +1417,14 @@ fn build_ui(app: &Application) {
     let list_box_apply = strategies_list_box.clone();
     let win_apply = window.clone();
     apply_button.connect_clicked(move |_| {
-        let mut selected_strategies = Vec::new();
-        let mut child = list_box_apply.first_child();
-        while let Some(widget) = child {
-            let content_widget = if let Ok(row) = widget.clone().downcast::<ListBoxRow>() {
-                row.child()
-            } else {
-                Some(widget.clone())
-            };
-            if let Some(content) = content_widget {
-                if let Ok(check) = content.downcast::<CheckButton>() {
-                    if check.is_active() {
-                    let val = if let Some(lbl) = check.label() {
-                        Some(lbl)
-                    } else if let Some(child) = check.child() {
-                        if let Ok(lbl) = child.downcast::<Label>() {
-                            Some(lbl.label())
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-                    if let Some(label_txt) = val {
-                        selected_strategies.push(label_txt.to_string());
-                    }
-                    }
-                }
-            }
-            child = widget.next_sibling();
-        }
-        if selected_strategies.is_empty() {
-             let dialog = adw::MessageDialog::builder()
-                .transient_for(&win_apply)
-                .heading(&t("Uyarı"))
-                .body(&t("Lütfen en az bir strateji seçin."))
-                .build();
-            dialog.add_response("ok", &t("Tamam"));
-            dialog.present();
-            return;
-        }
-        let combined_strategies = selected_strategies.join(" ");
-        println!("Applying: {}", combined_strategies);
-        log_to_file(&format!("Applying strategies: {}", combined_strategies));
-        let config_path = Path::new("/opt/zapret/config");
-        let content_res = fs::read_to_string(config_path).or_else(|_| {
-             let out = Command::new("pkexec").arg("cat").arg("/opt/zapret/config").output();
-             match out {
-                 Ok(o) if o.status.success() => Ok(String::from_utf8_lossy(&o.stdout).to_string()),
-                 _ => Err(io::Error::new(io::ErrorKind::PermissionDenied, t("Dosya okunamadı"))),
-             }
-        });
-        match content_res {
-            Ok(content) => {
-                let new_content = update_config_content(&content, &combined_strategies);
-                let temp_path = "/tmp/zapret_config_new";
-                if let Err(e) = fs::write(temp_path, &new_content) {
-                     let dialog = adw::MessageDialog::builder()
-                        .transient_for(&win_apply)
-                        .heading(&t("Hata"))
-                        .body(&t("Geçici dosya oluşturulamadı: {}").replace("{}", &e.to_string()))
-                        .build();
-                    dialog.add_response("ok", &t("Tamam"));
-                    dialog.present();
-                    return;
-                }
-                let init = get_init_system();
-                let restart_cmd = if init == "openrc" {
-                    "rc-service zapret restart"
-                } else if init == "runit" {
-                    "sv restart zapret"
-                } else {
-                    "systemctl restart zapret"
-                };
-                let cmd_script = format!("mv -f {} /opt/zapret/config && {}", temp_path, restart_cmd);
-                let res = Command::new("pkexec")
-                    .arg("sh")
-                    .arg("-c")
-                    .arg(cmd_script)
-                    .output();
-                match res {
-                    Ok(output) if output.status.success() => {
-                        log_to_file("Config file updated successfully and service restarted.");
-                        let dialog = adw::MessageDialog::builder()
-                            .transient_for(&win_apply)
-                            .heading(&t("Başarılı"))
-                            .body(&t("Stratejiler config dosyasına yazıldı ve Zapret servisi yeniden başlatıldı."))
-                            .build();
-                        dialog.add_response("ok", &t("Tamam"));
-                        dialog.present();
-                    },
-                    Ok(output) => {
-                         let err = String::from_utf8_lossy(&output.stderr);
-                         log_to_file(&format!("Service start error: {}", err));
-                         let dialog = adw::MessageDialog::builder()
-                            .transient_for(&win_apply)
-                            .heading(&t("Hata"))
-                            .body(&t("Servis başlatılamadı:\n{}").replace("{}", &err))
-                            .build();
-                        dialog.add_response("ok", &t("Tamam"));
-                        dialog.present();
-                    },
-                    Err(e) => {
-                         let dialog = adw::MessageDialog::builder()
-                            .transient_for(&win_apply)
-                            .heading(&t("Hata"))
-                            .body(&t("Komut hatası: {}").replace("{}", &e.to_string()))
-                            .build();
-                        dialog.add_response("ok", &t("Tamam"));
-                        dialog.present();
-                    }
-                }
-            },
-            Err(e) => {
-                 let dialog = adw::MessageDialog::builder()
-                    .transient_for(&win_apply)
-                    .heading(&t("Okuma Hatası"))
-                    .body(&t("Config dosyası okunamadı: {}").replace("{}", &e.to_string()))
-                    .build();
-                dialog.add_response("ok", &t("Tamam"));
-                dialog.present();
-            }
-        }
+        let selected_strategies = collect_selected_strategies(&list_box_apply);
+        show_apply_result(&win_apply, apply_strategies(&selected_strategies));
+    });
+    let list_box_profiles = strategies_list_box.clone();
+    let win_profiles = window.clone();
+    let profiles_box_show = profiles_box.clone();
+    profiles_popover.connect_show(move |popover| {
+        rebuild_profiles_menu(popover, &profiles_box_show, &list_box_profiles, &win_profiles);
     });
     if Path::new("/opt/zapret").exists() && get_config_path().exists() {
         delete_local_zapret_folder();
@@ -1131,23 +1450,10 @@ fn build_ui(app: &Application) {
                         }
                     }
                 }
+                let scores = benchmark::load_scores();
                 for strat in strategies {
                     let is_active = !config_content.is_empty() && config_content.contains(&strat);
-                    let child_label = Label::builder()
-                        .label(&strat)
-                        .wrap(true)
-                        .max_width_chars(50)
-                        .xalign(0.0)
-                        .build();
-                    let check = CheckButton::builder()
-                        .child(&child_label)
-                        .active(is_active)
-                        .margin_top(10)
-                        .margin_bottom(10)
-                        .margin_start(10)
-                        .margin_end(10)
-                        .build();
-                    strategies_list_box.append(&check);
+                    append_strategy_row(&strategies_list_box, &strat, is_active, &scores);
                 }
                 nav_view.push(&page_mgmt);
              }
@@ -1172,7 +1478,7 @@ fn build_ui(app: &Application) {
             button_clone.add_css_class("suggested-action");
             button_clone.set_sensitive(true);
             placeholder_label_clone.set_visible(true);
-            dns_warning_label_clone.set_visible(true);
+            dns_warning_label_clone.set_visible(dns_problem.get());
             progress_bar_clone.set_visible(false);
             status_label_clone.set_label(&t("Hazır"));
             status_label_clone.set_visible(false); 
@@ -1184,17 +1490,20 @@ fn build_ui(app: &Application) {
             status_label_check_clone.remove_css_class("error");
             status_label_check_clone.remove_css_class("success");
             conflict_list_label_clone.set_label("");
+            conflict_scrolled_clone.set_visible(false);
             force_continue_button_clone.set_visible(false);
             spinner_check_clone.set_spinning(true);
             spinner_check_clone.set_visible(true);
             let (tx, rx) = mpsc::channel();
             thread::spawn(move || {
                 thread::sleep(Duration::from_secs(1));
-                let conflicts = check_processes();
+                let conflicts = conflicts::detect();
                 let _ = tx.send(conflicts);
             });
             let lbl = status_label_check_clone.clone();
             let lst = conflict_list_label_clone.clone();
+            let conflict_box = conflict_list_box_clone.clone();
+            let conflict_scroll = conflict_scrolled_clone.clone();
             let btn = force_continue_button_clone.clone();
             let spn = spinner_check_clone.clone();
             let nav = nav_view_clone_for_check.clone();
@@ -1213,11 +1522,17 @@ fn build_ui(app: &Application) {
                                 n.replace(&[p.clone()]);
                                 glib::ControlFlow::Break
                             });
+                        } else if !conflicts.iter().any(conflicts::Conflict::blocks) {
+                            lbl.set_label(&t("Sorun bulunmadı."));
+                            lbl.add_css_class("success");
+                            populate_conflict_list(&conflict_box, &lst, &btn, &conflicts);
+                            conflict_scroll.set_visible(true);
+                            btn.set_visible(true);
                         } else {
                             lbl.set_label(&t("Çakışan Uygulamalar Tespit Edildi!"));
                             lbl.add_css_class("error");
-                            let list_str = conflicts.join(", ");
-                            lst.set_label(&t("Şu servisler kapatılmalı: {}").replace("{}", &list_str));
+                            populate_conflict_list(&conflict_box, &lst, &btn, &conflicts);
+                            conflict_scroll.set_visible(true);
                             btn.set_visible(true);
                         }
                         glib::ControlFlow::Break
@@ -1287,11 +1602,11 @@ fn build_ui(app: &Application) {
             }
         });
 
-        if check_network_manager() {
+        {
              let dialog = adw::MessageDialog::builder()
                 .transient_for(&window_clone) 
                 .heading(&t("DNS Ayarı"))
-                .body(&t("Mevcut DNS adresiniz Cloudflare ile değiştirilsin mi? (Bu işlemin ne anlama geldiğini bilmiyorsanız 'Evet' butonuna tıklayarak devam edebilirsiniz.)"))
+                .body(&t("Mevcut DNS adresiniz {} ile değiştirilsin mi? (Bu işlemin ne anlama geldiğini bilmiyorsanız 'Evet' butonuna tıklayarak devam edebilirsiniz.)").replace("{}", &dns::load().describe()))
                 .build();
             dialog.add_response("no", &t("Hayır"));
             dialog.add_response("yes", &t("Evet"));
@@ -1304,8 +1619,6 @@ fn build_ui(app: &Application) {
                 flow_clone(set_dns);
             });
             dialog.present();
-        } else {
-             run_installation_flow(false);
         }
     });
     force_continue_button.connect_clicked(move |_| {
@@ -1332,49 +1645,173 @@ fn build_ui(app: &Application) {
         }
         nav_view_clone_cancel.pop();
     });
-    let nav_view_clone_import_btn = nav_view.clone();
-    let page_test_clone_import = page_test.clone();
-    let lbl_test_clone_import = label_test_counter.clone();
-    let pid_clone_import = current_pid.clone();
-    let cf_clone_import = test_cancel_flag.clone();
-    let nav_mgmt_import = nav_view_clone_mgmt.clone();
-    let page_mgmt_import = page_mgmt_clone.clone();
-    let list_mgmt_import = list_box_mgmt.clone();
-    import_button.connect_clicked(move |_| {
-        let file_dialog = gtk::FileDialog::builder()
-            .title(&t("Strateji Dosyası Seç"))
-            .modal(true)
-            .accept_label(&t("İçe Aktar"))
+    let win_rules = window.clone();
+    rules_button.connect_clicked(move |_| {
+        show_rule_inspector(&win_rules);
+    });
+    let win_bench = window.clone();
+    let nav_bench = nav_view.clone();
+    let page_test_bench = page_test.clone();
+    let lbl_bench = label_test_counter.clone();
+    let cf_bench = test_cancel_flag.clone();
+    let list_bench = list_box_mgmt.clone();
+    benchmark_button.connect_clicked(move |_| {
+        let strategies = load_saved_strategies();
+        let saved_domains = domains::load_domains();
+        let mapping = domains::load_strategy_domains();
+        let targets: Vec<Vec<String>> = strategies
+            .iter()
+            .map(|s| {
+                let mapped = domains::domains_for_strategy(&mapping, s);
+                if mapped.is_empty() { saved_domains.clone() } else { mapped }
+            })
+            .collect();
+        if strategies.is_empty() || targets.iter().any(|d| d.is_empty()) {
+            let dialog = adw::MessageDialog::builder()
+                .transient_for(&win_bench)
+                .heading(&t("Hız Testi"))
+                .body(&t("Hız testi için kayıtlı stratejiler ve alan adları gerekli. Önce Blockcheck taraması yapın."))
+                .build();
+            dialog.add_response("ok", &t("Tamam"));
+            dialog.present();
+            return;
+        }
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&win_bench)
+            .heading(&t("Hız Testi"))
+            .body(&t("Her strateji sırayla geçici olarak çalıştırılır; TLS el sıkışma süresi, ilk bayta kadar geçen süre ve indirme hızı ölçülür. Test sırasında Zapret servisi durdurulur ve sonra yeniden başlatılır.\n\nHer alan adı için kaç ölçüm yapılsın?"))
             .build();
-        let filter = FileFilter::new();
-        filter.set_name(Some(&t("JSON Dosyaları")));
-        filter.add_pattern("*.json");
-        let filters = gtk::gio::ListStore::new::<FileFilter>();
-        filters.append(&filter);
-        file_dialog.set_filters(Some(&filters));
-        file_dialog.set_default_filter(Some(&filter));
-        let win_for_dialog = window_clone_import.clone();
-        let nav = nav_view_clone_import_btn.clone();
-        let page = page_test_clone_import.clone();
-        let lbl = lbl_test_clone_import.clone();
-        let pid = pid_clone_import.clone();
-        let cf = cf_clone_import.clone();
-        let list_box_mgmt_import_timer = list_mgmt_import.clone();
-        let nav_mgmt_import_timer = nav_mgmt_import.clone();
-        let page_mgmt_import_timer = page_mgmt_import.clone();
-        file_dialog.open(Some(&window_clone_import), None::<&gtk::gio::Cancellable>, move |result| {
-            if let Ok(file) = result {
-                if let Some(path) = file.path() {
-                    match validate_and_copy_strategies(&path) {
-                        Ok(_) => {
-                            cf.store(false, Ordering::Relaxed);
+        dialog.add_response("1", &t("1 Ölçüm"));
+        dialog.add_response("3", &t("3 Ölçüm"));
+        dialog.add_response("5", &t("5 Ölçüm"));
+        dialog.add_response("cancel", &t("Vazgeç"));
+        dialog.set_response_appearance("3", ResponseAppearance::Suggested);
+        let nav = nav_bench.clone();
+        let page = page_test_bench.clone();
+        let lbl = lbl_bench.clone();
+        let cf = cf_bench.clone();
+        let list = list_bench.clone();
+        let win = win_bench.clone();
+        dialog.connect_response(None, move |d, response| {
+            d.close();
+            let runs: u32 = match response.parse() {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+            cf.store(false, Ordering::Relaxed);
+            nav.push(&page);
+            lbl.set_label(&t("Hız testi başlatılıyor..."));
+            let (sender, receiver) = mpsc::channel();
+            let strategies_thread = strategies.clone();
+            let targets_thread = targets.clone();
+            let cf_thread = cf.clone();
+            thread::spawn(move || {
+                benchmark::run_benchmark(strategies_thread, targets_thread, runs, sender, cf_thread);
+            });
+            let nav_timer = nav.clone();
+            let lbl_timer = lbl.clone();
+            let list_timer = list.clone();
+            let win_timer = win.clone();
+            glib::timeout_add_local(Duration::from_millis(50), move || {
+                match receiver.try_recv() {
+                    Ok(benchmark::BenchMsg::Progress(text)) => {
+                        lbl_timer.set_label(&text);
+                        glib::ControlFlow::Continue
+                    },
+                    Ok(benchmark::BenchMsg::Finished(result)) => {
+                        nav_timer.pop();
+                        match result {
+                            Ok(results) => {
+                                if let Err(e) = benchmark::save_scores(&results) {
+                                    log_to_file(&format!("Could not save benchmark scores: {}", e));
+                                }
+                                let active = collect_selected_strategies(&list_timer);
+                                let mut sorted = load_saved_strategies();
+                                benchmark::sort_by_score(&mut sorted);
+                                if let Err(e) = save_strategies_to_json(&sorted) {
+                                    log_to_file(&format!("Could not save sorted strategies: {}", e));
+                                }
+                                populate_strategy_list(&list_timer, &sorted, &active);
+                                let best = results.first();
+                                let body = match best {
+                                    Some(r) if r.score > 0.0 => t("En iyi strateji:\n{s}\n\nPuan: {p}\nTLS el sıkışma: {h} ms\nİlk bayt: {f} ms\nHız: {v} KB/s")
+                                        .replace("{s}", &r.strategy)
+                                        .replace("{p}", &format!("{:.0}", r.score))
+                                        .replace("{h}", &format!("{:.0}", r.handshake_ms))
+                                        .replace("{f}", &format!("{:.0}", r.ttfb_ms))
+                                        .replace("{v}", &format!("{:.0}", r.speed / 1024.0)),
+                                    _ => t("Hiçbir strateji ile alan adlarına erişilemedi."),
+                                };
+                                let dialog = adw::MessageDialog::builder()
+                                    .transient_for(&win_timer)
+                                    .heading(&t("Hız Testi Tamamlandı"))
+                                    .body(&body)
+                                    .build();
+                                dialog.add_response("ok", &t("Tamam"));
+                                dialog.present();
+                            },
+                            Err(e) => {
+                                let dialog = adw::MessageDialog::builder()
+                                    .transient_for(&win_timer)
+                                    .heading(&t("Hata"))
+                                    .body(&t("Hız testi çalıştırılamadı: {}").replace("{}", &e.to_string()))
+                                    .build();
+                                dialog.add_response("ok", &t("Tamam"));
+                                dialog.present();
+                            }
+                        }
+                        glib::ControlFlow::Break
+                    },
+                    Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+                }
+            });
+        });
+        dialog.present();
+    });
+    let nav_view_clone_import_btn = nav_view.clone();
+    let page_test_clone_import = page_test.clone();
+    let lbl_test_clone_import = label_test_counter.clone();
+    let pid_clone_import = current_pid.clone();
+    let cf_clone_import = test_cancel_flag.clone();
+    let nav_mgmt_import = nav_view_clone_mgmt.clone();
+    let page_mgmt_import = page_mgmt_clone.clone();
+    let list_mgmt_import = list_box_mgmt.clone();
+    import_button.connect_clicked(move |_| {
+        let file_dialog = gtk::FileDialog::builder()
+            .title(&t("Strateji Dosyası Seç"))
+            .modal(true)
+            .accept_label(&t("İçe Aktar"))
+            .build();
+        let filter = FileFilter::new();
+        filter.set_name(Some(&t("JSON Dosyaları")));
+        filter.add_pattern("*.json");
+        let filters = gtk::gio::ListStore::new::<FileFilter>();
+        filters.append(&filter);
+        file_dialog.set_filters(Some(&filters));
+        file_dialog.set_default_filter(Some(&filter));
+        let win_for_dialog = window_clone_import.clone();
+        let nav = nav_view_clone_import_btn.clone();
+        let page = page_test_clone_import.clone();
+        let lbl = lbl_test_clone_import.clone();
+        let pid = pid_clone_import.clone();
+        let cf = cf_clone_import.clone();
+        let list_box_mgmt_import_timer = list_mgmt_import.clone();
+        let nav_mgmt_import_timer = nav_mgmt_import.clone();
+        let page_mgmt_import_timer = page_mgmt_import.clone();
+        file_dialog.open(Some(&window_clone_import), None::<&gtk::gio::Cancellable>, move |result| {
+            if let Ok(file) = result {
+                if let Some(path) = file.path() {
+                    match validate_and_copy_strategies(&path) {
+                        Ok(_) => {
+                            cf.store(false, Ordering::Relaxed);
                             nav.push(&page);
                             lbl.set_label(&t("Zapret Kuruluyor (/opt/zapret)..."));
                             let (sender, receiver) = mpsc::channel();
                             let sender_thread = sender.clone();
                             let cf_thread = cf.clone();
                             thread::spawn(move || {
-                                run_easy_install_script(sender_thread, cf_thread);
+                                run_easy_install_script(sender_thread, cf_thread, false);
                             });
                             let nav_timer = nav.clone();
                             let win_timer = win_for_dialog.clone();
@@ -1402,6 +1839,7 @@ fn build_ui(app: &Application) {
                                                 nav_timer.pop();
                                                 match result {
                                                     Ok(_) => {
+                                                        notify::send("install", &t("Kurulum Tamamlandı"), &t("Zapret /opt/zapret dizinine kuruldu."), false, &[]);
                                                         let mut child = list_box_mgmt_import_timer.first_child();
                                                         while let Some(widget) = child {
                                                             let next = widget.next_sibling();
@@ -1428,21 +1866,9 @@ fn build_ui(app: &Application) {
                                                                         }
                                                                     }
                                                                 }
+                                                                let scores = benchmark::load_scores();
                                                                 for strat in strategies {
-                                                                    let child_label = Label::builder()
-                                                                        .label(&strat)
-                                                                        .wrap(true)
-                                                                        .max_width_chars(50)
-                                                                        .xalign(0.0)
-                                                                        .build();
-                                                                    let check = CheckButton::builder()
-                                                                        .child(&child_label)
-                                                                        .margin_top(10)
-                                                                        .margin_bottom(10)
-                                                                        .margin_start(10)
-                                                                        .margin_end(10)
-                                                                        .build();
-                                                                    list_box_mgmt_import_timer.append(&check);
+                                                                    append_strategy_row(&list_box_mgmt_import_timer, &strat, false, &scores);
                                                                 }
                                                             }
                                                         }
@@ -1482,30 +1908,92 @@ fn build_ui(app: &Application) {
             }
         });
     });
+    let entries_container_presets = entries_container.clone();
+    let window_presets = window.clone();
+    presets_button.connect_clicked(move |_| {
+        let presets = presets::load_presets();
+        let presets_box = Box::new(Orientation::Vertical, 5);
+        let mut checks = Vec::new();
+        for preset in &presets {
+            let check = CheckButton::builder()
+                .label(&format!("{} ({})", preset.name, preset.domains.len()))
+                .tooltip_text(&preset.domains.join(", "))
+                .build();
+            presets_box.append(&check);
+            checks.push(check);
+        }
+        let scrolled = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .min_content_height(250)
+            .child(&presets_box)
+            .build();
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&window_presets)
+            .heading(&t("Hazır Listeler"))
+            .body(&t("Seçtiğiniz servislerin ihtiyaç duyduğu tüm alan adları (CDN dahil) listeye eklenir. Kendi listelerinizi presets klasörüne .txt dosyası olarak ekleyebilirsiniz."))
+            .extra_child(&scrolled)
+            .build();
+        dialog.add_response("folder", &t("Klasörü Aç"));
+        dialog.add_response("cancel", &t("İptal"));
+        dialog.add_response("add", &t("Ekle"));
+        dialog.set_response_appearance("add", ResponseAppearance::Suggested);
+        let container = entries_container_presets.clone();
+        dialog.connect_response(None, move |d, response| {
+            d.close();
+            if response == "folder" {
+                let dir = presets::get_presets_dir();
+                let _ = fs::create_dir_all(&dir);
+                let _ = Command::new("xdg-open").arg(dir).spawn();
+            } else if response == "add" {
+                let mut selected = Vec::new();
+                for (check, preset) in checks.iter().zip(presets.iter()) {
+                    if check.is_active() {
+                        selected.extend(preset.domains.iter().cloned());
+                    }
+                }
+                fill_domain_entries(&container, &selected);
+            }
+        });
+        dialog.present();
+    });
     let entries_container_read = entries_container.clone();
     let window_clone_msg = window.clone();
+    let rescan_only_finish = rescan_only.clone();
     finish_button.connect_clicked(move |_| {
-        let mut domains = Vec::new();
+        let mut domains: Vec<String> = Vec::new();
+        let mut invalid = Vec::new();
         let mut current_child = entries_container_read.first_child();
         while let Some(child) = current_child {
+            current_child = child.next_sibling();
             if let Ok(entry) = child.clone().downcast::<Entry>() {
                 let text = entry.text();
-                if !text.is_empty() {
-                    let domain = text.to_string();
-                    if domain.starts_with("http://") || domain.starts_with("https://") || domain.starts_with("www.") {
-                        let dialog = adw::MessageDialog::builder()
-                            .transient_for(&window_clone_msg)
-                            .heading(&t("Hatalı Alan Adı"))
-                            .body(&t("'{}' geçerli bir alan adı formatı değil.\nLütfen 'http://', 'https://' veya 'www.' kullanmadan sadece alan adını girin (örnek: google.com).").replace("{}", &domain))
-                            .build();
-                        dialog.add_response("ok", &t("Tamam"));
-                        dialog.present();
-                        return;
-                    }
-                    domains.push(domain);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                match domains::normalize_domain(&text) {
+                    Ok(domain) => {
+                        if domains.contains(&domain) {
+                            entries_container_read.remove(&entry);
+                        } else {
+                            if domain != text.as_str() {
+                                entry.set_text(&domain);
+                            }
+                            domains.push(domain);
+                        }
+                    },
+                    Err(e) => invalid.push(format!("{}: {}", text, e)),
                 }
             }
-            current_child = child.next_sibling(); 
+        }
+        if !invalid.is_empty() {
+            let dialog = adw::MessageDialog::builder()
+                .transient_for(&window_clone_msg)
+                .heading(&t("Hatalı Alan Adı"))
+                .body(&t("Aşağıdaki girdiler geçerli bir alan adı değil:\n{}").replace("{}", &invalid.join("\n")))
+                .build();
+            dialog.add_response("ok", &t("Tamam"));
+            dialog.present();
+            return;
         }
         if domains.is_empty() {
             let dialog = adw::MessageDialog::builder()
@@ -1538,6 +2026,8 @@ fn build_ui(app: &Application) {
         let nav_mgmt = nav_view_clone_mgmt.clone();
         let page_mgmt = page_mgmt_clone.clone();
         let list_mgmt = list_box_mgmt.clone();
+        let rescan = rescan_only_finish.clone();
+        let entries = entries_container_read.clone();
         dialog.connect_response(None, move |d: &adw::MessageDialog, response_id| {
             let (repeats, scan_level) = match response_id {
                 "quick" => (1, "quick".to_string()),
@@ -1549,6 +2039,16 @@ fn build_ui(app: &Application) {
                 }
             };
             d.close();
+            let mut saved = if rescan.replace(false) { domains::load_domains() } else { Vec::new() };
+            for domain in &d_list {
+                if !saved.contains(domain) {
+                    saved.push(domain.clone());
+                }
+            }
+            if let Err(e) = domains::save_domains(&saved) {
+                log_to_file(&format!("Could not save domain list: {}", e));
+            }
+            replace_domain_entries(&entries, &saved);
             cf.store(false, Ordering::Relaxed);
             let cf_thread = cf.clone();
             let cf_install = cf.clone();
@@ -1593,6 +2093,20 @@ fn build_ui(app: &Application) {
                                 if let Ok(mut guard) = pid_timer.lock() {
                                     *guard = None;
                                 }
+                                if !win_timer.is_active() {
+                                    match &result {
+                                        Ok(strategies) if !strategies.is_empty() => {
+                                            notify::send("blockcheck", &t("Blockcheck Tamamlandı"), &t("{} çalışan strateji bulundu.").replace("{}", &strategies.len().to_string()), false, &[]);
+                                        },
+                                        Ok(_) => {
+                                            notify::send("blockcheck", &t("Blockcheck Tamamlandı"), &t("Blockcheck tamamlandı ancak çalışan bir strateji bulunamadı."), false, &[]);
+                                        },
+                                        Err(e) if e.to_string() != "İptal edildi" => {
+                                            notify::send("blockcheck", &t("Strateji Bulma Hatası"), &t("Blockcheck çalıştırılamadı: {}").replace("{}", &e.to_string()), false, &[(t("Kayıtları Aç"), notify::ACTION_OPEN_LOGS)]);
+                                        },
+                                        Err(_) => {}
+                                    }
+                                }
                                 match result {
                                     Ok(strategies) => {
                                         if let Err(e) = save_strategies_to_json(&strategies) {
@@ -1621,7 +2135,7 @@ fn build_ui(app: &Application) {
                                             let s = sender_install.clone();
                                             let c = cf_install.clone();
                                             thread::spawn(move || {
-                                                run_easy_install_script(s, c);
+                                                run_easy_install_script(s, c, false);
                                             });
                                             glib::ControlFlow::Continue
                                         }
@@ -1647,6 +2161,7 @@ fn build_ui(app: &Application) {
                                 }
                                 match result {
                                     Ok(_) => {
+                                        notify::send("install", &t("Kurulum Tamamlandı"), &t("Zapret /opt/zapret dizinine kuruldu."), false, &[]);
                                         let mut child = list_box_mgmt_timer.first_child();
                                         while let Some(widget) = child {
                                             let next = widget.next_sibling();
@@ -1673,21 +2188,9 @@ fn build_ui(app: &Application) {
                                                         }
                                                     }
                                                 }
+                                                let scores = benchmark::load_scores();
                                                 for strat in strategies {
-                                                    let child_label = Label::builder()
-                                                        .label(&strat)
-                                                        .wrap(true)
-                                                        .max_width_chars(50)
-                                                        .xalign(0.0)
-                                                        .build();
-                                                    let check = CheckButton::builder()
-                                                        .child(&child_label)
-                                                        .margin_top(10)
-                                                        .margin_bottom(10)
-                                                        .margin_start(10)
-                                                        .margin_end(10)
-                                                        .build();
-                                                    list_box_mgmt_timer.append(&check);
+                                                    append_strategy_row(&list_box_mgmt_timer, &strat, false, &scores);
                                                 }
                                             }
                                         }
@@ -1716,6 +2219,99 @@ fn build_ui(app: &Application) {
         });
         dialog.present();
     });
+    let monitor_running = Rc::new(Cell::new(false));
+    let monitor_alerted = Rc::new(Cell::new(false));
+    let win_monitor = window.clone();
+    let nav_monitor = nav_view.clone();
+    let page2_monitor = page2.clone();
+    let entries_monitor = entries_container.clone();
+    let rescan_only_monitor = rescan_only.clone();
+    glib::timeout_add_local(Duration::from_secs(60), move || {
+        if !monitor::is_enabled() || monitor_running.get() {
+            return glib::ControlFlow::Continue;
+        }
+        let now = glib::DateTime::now_utc().map(|d| d.to_unix()).unwrap_or(0);
+        let last = monitor::load_history().last().map(|s| s.time).unwrap_or(0);
+        if now - last < monitor::interval_minutes() as i64 * 60 {
+            return glib::ControlFlow::Continue;
+        }
+        let saved = domains::load_domains();
+        if saved.is_empty() || !get_service_status().0 {
+            return glib::ControlFlow::Continue;
+        }
+        monitor_running.set(true);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(monitor::run_probe(&saved, now));
+        });
+        let running = monitor_running.clone();
+        let alerted = monitor_alerted.clone();
+        let win = win_monitor.clone();
+        let nav = nav_monitor.clone();
+        let page2 = page2_monitor.clone();
+        let entries = entries_monitor.clone();
+        let rescan_only = rescan_only_monitor.clone();
+        let stats_row = monitor_stats_row.clone();
+        glib::timeout_add_local(Duration::from_millis(200), move || {
+            let sample = match receiver.try_recv() {
+                Ok(sample) => sample,
+                Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    running.set(false);
+                    return glib::ControlFlow::Break;
+                }
+            };
+            running.set(false);
+            log_to_file(&format!("Health check: {}/{} reachable. Failed: {}", sample.succeeded, sample.total, sample.failed.join(", ")));
+            if let Err(e) = monitor::append_history(&sample) {
+                log_to_file(&format!("Could not save health history: {}", e));
+            }
+            stats_row.set_subtitle(&monitor::summary());
+            if sample.rate() >= monitor::threshold() {
+                alerted.set(false);
+                return glib::ControlFlow::Break;
+            }
+            if alerted.get() {
+                return glib::ControlFlow::Break;
+            }
+            alerted.set(true);
+            notify::send(
+                "monitor",
+                &t("Erişim Sorunu"),
+                &t("Kayıtlı alan adlarının yalnızca %{r} kadarına erişilebiliyor.").replace("{r}", &sample.rate().to_string()),
+                true,
+                &[],
+            );
+            let dialog = adw::MessageDialog::builder()
+                .transient_for(&win)
+                .heading(&t("Erişim Sorunu"))
+                .body(&t("Kayıtlı alan adlarının yalnızca %{r} kadarına erişilebiliyor. Sağlayıcınız DPI yöntemini değiştirmiş olabilir.\n\nErişilemeyenler:\n{d}\n\nBu alan adlarıyla yeni bir Blockcheck taraması başlatılsın mı?")
+                    .replace("{r}", &sample.rate().to_string())
+                    .replace("{d}", &sample.failed.join("\n")))
+                .build();
+            dialog.add_response("ignore", &t("Yoksay"));
+            dialog.add_response("scan", &t("Yeniden Tara"));
+            dialog.set_response_appearance("scan", ResponseAppearance::Suggested);
+            let failed = sample.failed.clone();
+            let nav = nav.clone();
+            let page2 = page2.clone();
+            let entries = entries.clone();
+            let rescan_only = rescan_only.clone();
+            dialog.connect_response(None, move |d, response| {
+                d.close();
+                if response != "scan" {
+                    return;
+                }
+                rescan_only.set(true);
+                replace_domain_entries(&entries, &failed);
+                show_domain_page(&nav, &page2);
+            });
+            win.present();
+            dialog.present();
+            glib::ControlFlow::Break
+        });
+        glib::ControlFlow::Continue
+    });
     window.present();
 }
 fn validate_and_copy_strategies(path: &Path) -> io::Result<()> {
@@ -1724,6 +2320,39 @@ fn validate_and_copy_strategies(path: &Path) -> io::Result<()> {
     if !trimmed.starts_with('[') || !trimmed.ends_with(']') {
         return Err(io::Error::new(io::ErrorKind::InvalidData, t("Dosya geçerli bir JSON listesi (array) formatında değil.")));
     }
+    let strategies = parse_strategies_json(trimmed);
+    if strategies.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, t("Dosya içerisinde strateji bulunamadı.")));
+    }
+    for s in &strategies {
+        if !s.trim().starts_with("--") {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, t("Geçersiz strateji: '{}'. Stratejiler '--' ile başlamalıdır.").replace("{}", s)));
+        }
+    }
+    let problems = lint_strategies(&strategies);
+    if !problems.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, t("Bazı stratejiler geçersiz:\n{}").replace("{}", &problems)));
+    }
+    let dest = get_config_path();
+    fs::write(dest, content)?;
+    Ok(())
+}
+fn lint_strategies(strategies: &[String]) -> String {
+    let supported = lint::supported_options();
+    let mut problems = Vec::new();
+    for strategy in strategies {
+        let issues = lint::lint_strategy(strategy, supported.as_deref());
+        if lint::has_errors(&issues) {
+            problems.push(format!("{}\n{}", strategy, lint::format_issues(&issues)));
+        }
+    }
+    problems.join("\n\n")
+}
+fn parse_strategies_json(content: &str) -> Vec<String> {
+    let trimmed = content.trim();
+    if !trimmed.starts_with('[') || !trimmed.ends_with(']') {
+        return Vec::new();
+    }
     let mut in_string = false;
     let mut escaped = false;
     let mut current_string = String::new();
@@ -1742,23 +2371,274 @@ fn validate_and_copy_strategies(path: &Path) -> io::Result<()> {
             } else {
                 current_string.push(c);
             }
-        } else {
-            if c == '"' {
-                in_string = true;
+        } else if c == '"' {
+            in_string = true;
+        }
+    }
+    strategies
+}
+fn load_saved_strategies() -> Vec<String> {
+    fs::read_to_string(get_config_path())
+        .map(|content| parse_strategies_json(&content))
+        .unwrap_or_default()
+}
+fn service_status_summary(is_active: bool, status_text: &str) -> String {
+    if is_active {
+        format!("Zapret: {}", t("Çalışıyor (Active)"))
+    } else {
+        format!("Zapret: {}", t("Durdu ({})").replace("{}", status_text))
+    }
+}
+fn start_tray(app: &Application, window: &ApplicationWindow, holder: &Rc<RefCell<Option<tray::Tray>>>, list_box: &ListBox) {
+    if holder.borrow().is_some() {
+        return;
+    }
+    let Some(connection) = app.dbus_connection() else {
+        log_to_file("No D-Bus session connection, tray icon is not available.");
+        return;
+    };
+    let win = window.clone();
+    let app_quit = app.clone();
+    let list = list_box.clone();
+    let result = tray::Tray::start(&connection, move |action| match action {
+        tray::TrayAction::Start => control_service("start"),
+        tray::TrayAction::Stop => control_service("stop"),
+        tray::TrayAction::Restart => control_service("restart"),
+        tray::TrayAction::ApplyProfile(name) => match apply_profile(&name) {
+            Ok(note) => {
+                if let Some(note) = note {
+                    notify::send("apply", &t("Stratejiler Uygulandı"), &note, false, &[]);
+                }
+                if let Some(profile) = profiles::find_profile(&name) {
+                    set_selected_strategies(&list, &profile.strategies);
+                }
+            },
+            Err((heading, body)) => notify::send("apply", &heading, &body, true, &[]),
+        },
+        tray::TrayAction::ShowWindow => win.present(),
+        tray::TrayAction::Quit => app_quit.quit(),
+    });
+    match result {
+        Ok(tray) => {
+            let (is_active, status_text) = get_service_status();
+            tray.update(is_active, &service_status_summary(is_active, &status_text), profiles::profile_names(), profiles::active_profile());
+            log_to_file("Tray icon started.");
+            *holder.borrow_mut() = Some(tray);
+        },
+        Err(e) => log_to_file(&format!("Could not start tray icon: {}", e)),
+    }
+}
+fn show_apply_result(window: &ApplicationWindow, result: Result<Option<String>, (String, String)>) {
+    let (heading, body) = match result {
+        Ok(None) => (t("Başarılı"), t("Stratejiler config dosyasına yazıldı ve Zapret servisi yeniden başlatıldı.")),
+        Ok(Some(note)) => (t("Başarılı"), format!("{}\n\n{}", t("Stratejiler config dosyasına yazıldı ve Zapret servisi yeniden başlatıldı."), note)),
+        Err(error) => error,
+    };
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(window)
+        .heading(&heading)
+        .body(&body)
+        .build();
+    dialog.add_response("ok", &t("Tamam"));
+    dialog.present();
+}
+fn apply_profile(name: &str) -> Result<Option<String>, (String, String)> {
+    let profile = profiles::find_profile(name)
+        .ok_or_else(|| (t("Hata"), t("Profil bulunamadı: {}").replace("{}", name)))?;
+    let note = apply_strategies(&profile.strategies)?;
+    if let Err(e) = profiles::set_active_profile(name) {
+        log_to_file(&format!("Could not save active profile: {}", e));
+    }
+    Ok(note)
+}
+fn rebuild_profiles_menu(popover: &gtk::Popover, container: &Box, list_box: &ListBox, window: &ApplicationWindow) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+    let active = profiles::active_profile();
+    let all_profiles = profiles::load_profiles();
+    if all_profiles.is_empty() {
+        let empty_label = Label::builder()
+            .label(&t("Henüz profil yok."))
+            .css_classes(vec!["dim-label"])
+            .margin_top(5)
+            .margin_bottom(5)
+            .build();
+        container.append(&empty_label);
+    }
+    for profile in all_profiles {
+        let row = Box::new(Orientation::Horizontal, 5);
+        let apply_btn = Button::builder()
+            .label(&profile.name)
+            .tooltip_text(&profile.strategies.join("\n"))
+            .css_classes(vec!["flat"])
+            .hexpand(true)
+            .build();
+        if active.as_deref() == Some(profile.name.as_str()) {
+            apply_btn.add_css_class("accent");
+        }
+        let popover_apply = popover.clone();
+        let list_apply = list_box.clone();
+        let win_apply = window.clone();
+        let name_apply = profile.name.clone();
+        apply_btn.connect_clicked(move |_| {
+            popover_apply.popdown();
+            let result = apply_profile(&name_apply);
+            if result.is_ok() {
+                if let Some(p) = profiles::find_profile(&name_apply) {
+                    set_selected_strategies(&list_apply, &p.strategies);
+                }
+            }
+            show_apply_result(&win_apply, result);
+        });
+        let delete_btn = Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(&t("Profili Sil"))
+            .css_classes(vec!["flat"])
+            .build();
+        let popover_delete = popover.clone();
+        let name_delete = profile.name.clone();
+        delete_btn.connect_clicked(move |_| {
+            if let Err(e) = profiles::delete_profile(&name_delete) {
+                log_to_file(&format!("Could not delete profile: {}", e));
+            }
+            popover_delete.popdown();
+        });
+        row.append(&apply_btn);
+        row.append(&delete_btn);
+        container.append(&row);
+    }
+    container.append(&gtk::Separator::new(Orientation::Horizontal));
+    let save_btn = Button::builder()
+        .label(&t("Seçimi Profil Olarak Kaydet..."))
+        .css_classes(vec!["flat"])
+        .build();
+    let popover_save = popover.clone();
+    let list_save = list_box.clone();
+    let win_save = window.clone();
+    save_btn.connect_clicked(move |_| {
+        popover_save.popdown();
+        let selected = collect_selected_strategies(&list_save);
+        if selected.is_empty() {
+            show_apply_result(&win_save, Err((t("Uyarı"), t("Lütfen en az bir strateji seçin."))));
+            return;
+        }
+        let name_entry = Entry::builder()
+            .placeholder_text(&t("Profil adı"))
+            .activates_default(true)
+            .build();
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&win_save)
+            .heading(&t("Profil Olarak Kaydet"))
+            .body(&t("Seçili {} strateji bu adla kaydedilecek. Aynı adlı bir profil varsa üzerine yazılır.").replace("{}", &selected.len().to_string()))
+            .extra_child(&name_entry)
+            .default_response("save")
+            .build();
+        dialog.add_response("cancel", &t("İptal"));
+        dialog.add_response("save", &t("Kaydet"));
+        dialog.set_response_appearance("save", ResponseAppearance::Suggested);
+        dialog.connect_response(None, move |d, response| {
+            d.close();
+            let name = name_entry.text().trim().to_string();
+            if response != "save" || name.is_empty() {
+                return;
+            }
+            match profiles::save_profile(&name, &selected) {
+                Ok(()) => log_to_file(&format!("Profile saved: {}", name)),
+                Err(e) => log_to_file(&format!("Could not save profile {}: {}", name, e)),
             }
+        });
+        dialog.present();
+    });
+    container.append(&save_btn);
+}
+fn set_selected_strategies(list_box: &ListBox, strategies: &[String]) {
+    let mut child = list_box.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if let Some(check) = strategy_row_check(&widget) {
+            let selected = strategy_check_text(&check).map(|s| strategies.contains(&s)).unwrap_or(false);
+            check.set_active(selected);
         }
     }
-    if strategies.is_empty() {
-        return Err(io::Error::new(io::ErrorKind::InvalidData, t("Dosya içerisinde strateji bulunamadı.")));
+}
+fn apply_strategies(selected_strategies: &[String]) -> Result<Option<String>, (String, String)> {
+    if selected_strategies.is_empty() {
+        return Err((t("Uyarı"), t("Lütfen en az bir strateji seçin.")));
     }
-    for s in strategies {
-        if !s.trim().starts_with("--") {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, t("Geçersiz strateji: '{}'. Stratejiler '--' ile başlamalıdır.").replace("{}", &s)));
+    let problems = lint_strategies(selected_strategies);
+    if !problems.is_empty() {
+        return Err((t("Strateji Hatası"), t("Seçilen stratejiler kurulu nfqws ile uyumlu değil:\n\n{}").replace("{}", &problems)));
+    }
+    let plan = nfqws::plan_profiles(selected_strategies, &domains::load_domains(), &domains::load_strategy_domains());
+    let note = (!plan.idle.is_empty()).then(|| {
+        t("Şu stratejilere alan adı atanmadığı ve varsayılan profil zaten başka bir stratejiyle aynı trafiği kapsadığı için yazılmadı:\n\n{}\n\nKullanmak için bunlara alan adı atayın.")
+            .replace("{}", &plan.idle.iter().map(|s| format!("• {}", s)).collect::<Vec<_>>().join("\n"))
+    });
+    if let Some(note) = &note {
+        log_to_file(note);
+    }
+    let combined_strategies = nfqws::compose_nfqws_opt(&plan.profiles);
+    println!("Applying: {}", combined_strategies);
+    log_to_file(&format!("Applying strategies: {}", combined_strategies));
+    let mut install_hostlist_cmd = format!("mkdir -p {} && rm -f {}/zapret-gtk-hosts*.txt && ", nfqws::HOSTLIST_DIR, nfqws::HOSTLIST_DIR);
+    for (i, (path, list)) in plan.hostlists.iter().enumerate() {
+        let temp_hostlist = format!("/tmp/zapret-gtk-hosts-{}.txt", i);
+        if let Err(e) = fs::write(&temp_hostlist, list.join("\n") + "\n") {
+            return Err((t("Hata"), t("Geçici dosya oluşturulamadı: {}").replace("{}", &e.to_string())));
+        }
+        install_hostlist_cmd.push_str(&format!("mv -f {} {} && ", temp_hostlist, path));
+    }
+    let config_path = Path::new("/opt/zapret/config");
+    let content_res = fs::read_to_string(config_path).or_else(|_| {
+         let out = Command::new("pkexec").arg("cat").arg("/opt/zapret/config").output();
+         match out {
+             Ok(o) if o.status.success() => Ok(String::from_utf8_lossy(&o.stdout).to_string()),
+             _ => Err(io::Error::new(io::ErrorKind::PermissionDenied, t("Dosya okunamadı"))),
+         }
+    });
+    let content = content_res.map_err(|e| (t("Okuma Hatası"), t("Config dosyası okunamadı: {}").replace("{}", &e.to_string())))?;
+    let backend = firewall::resolve_backend(&get_distro_id());
+    let new_content = firewall::set_fwtype(&update_config_content(&content, &combined_strategies), backend);
+    log_to_file(&format!("Firewall backend: {}", backend.fwtype()));
+    let temp_path = "/tmp/zapret_config_new";
+    if let Err(e) = fs::write(temp_path, &new_content) {
+        return Err((t("Hata"), t("Geçici dosya oluşturulamadı: {}").replace("{}", &e.to_string())));
+    }
+    let init = get_init_system();
+    let restart_cmd = if init == "openrc" {
+        "rc-service zapret restart"
+    } else if init == "runit" {
+        "sv restart zapret"
+    } else {
+        "systemctl restart zapret"
+    };
+    let cmd_script = format!("{}mv -f {} /opt/zapret/config && {}", install_hostlist_cmd, temp_path, restart_cmd);
+    EXPECTED_STOP.store(true, Ordering::Relaxed);
+    let res = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(cmd_script)
+        .output();
+    match res {
+        Ok(output) if output.status.success() => {
+            log_to_file("Config file updated successfully and service restarted.");
+            notify::send("apply", &t("Stratejiler Uygulandı"), &t("Stratejiler config dosyasına yazıldı ve Zapret servisi yeniden başlatıldı."), false, &[]);
+            Ok(note)
+        },
+        Ok(output) => {
+            let err = String::from_utf8_lossy(&output.stderr);
+            log_to_file(&format!("Service start error: {}", err));
+            let body = t("Servis başlatılamadı:\n{}").replace("{}", &err);
+            notify::send("apply", &t("Uygulama Başarısız"), &body, true, &[(t("Kayıtları Aç"), notify::ACTION_OPEN_LOGS)]);
+            Err((t("Hata"), body))
+        },
+        Err(e) => {
+            let body = t("Komut hatası: {}").replace("{}", &e.to_string());
+            notify::send("apply", &t("Uygulama Başarısız"), &body, true, &[]);
+            Err((t("Hata"), body))
         }
     }
-    let dest = get_config_path();
-    fs::write(dest, content)?;
-    Ok(())
 }
 fn update_config_content(content: &str, new_opt: &str) -> String {
     let var_name = "NFQWS_OPT=\"";
@@ -1872,6 +2752,22 @@ fn run_blockcheck_process(domains: Vec<String>, repeats: usize, scan_level: Stri
             }
             res
         };
+        let mut tested_pairs: Vec<(String, String)> = Vec::new();
+        let mut record_tested = |prefix: &str, strategy: &str| {
+            let mut matched: Vec<&String> = domains
+                .iter()
+                .filter(|d| prefix.split(|c: char| c.is_whitespace() || c == ':').any(|tok| tok == d.as_str()))
+                .collect();
+            if matched.is_empty() {
+                matched = domains.iter().collect();
+            }
+            for domain in matched {
+                let pair = (domain.clone(), strategy.to_string());
+                if !tested_pairs.contains(&pair) {
+                    tested_pairs.push(pair);
+                }
+            }
+        };
         let clean_lines: Vec<String> = full_output.lines().map(|l| strip_ansi(l)).collect();
         let has_common = clean_lines.iter().any(|l| l.contains("* COMMON"));
         let target_header = if has_common { "* COMMON" } else { "* SUMMARY" };
@@ -1892,6 +2788,7 @@ fn run_blockcheck_process(domains: Vec<String>, repeats: usize, scan_level: Stri
                 if let Some(idx) = trimmed.find("nfqws ") {
                     if !trimmed.contains("checking") && !trimmed.contains(">>") && !trimmed.contains("not working") {
                         let strategy = trimmed[idx + 6..].trim().to_string();
+                        record_tested(&trimmed[..idx], &strategy);
                         strategies.push(strategy);
                     }
                 }
@@ -1903,6 +2800,7 @@ fn run_blockcheck_process(domains: Vec<String>, repeats: usize, scan_level: Stri
                  if let Some(idx) = trimmed.find("nfqws ") {
                      if !trimmed.contains("checking") && !trimmed.contains(">>") && !trimmed.contains("not working") {
                         let strategy = trimmed[idx + 6..].trim().to_string();
+                        record_tested(&trimmed[..idx], &strategy);
                         if !strategies.contains(&strategy) {
                             strategies.push(strategy);
                         }
@@ -1910,6 +2808,9 @@ fn run_blockcheck_process(domains: Vec<String>, repeats: usize, scan_level: Stri
                  }
              }
         }
+        if let Err(e) = domains::save_tested_domains(&tested_pairs) {
+            log_to_file(&format!("Could not save tested domains: {}", e));
+        }
         log_to_file(&format!("Blockcheck completed. {} strategies found.", strategies.len()));
         let _ = sender.send(TestMsg::Finished(Ok(strategies)));
     } else {
@@ -1917,7 +2818,7 @@ fn run_blockcheck_process(domains: Vec<String>, repeats: usize, scan_level: Stri
         let _ = sender.send(TestMsg::Finished(Err(io::Error::new(io::ErrorKind::Other, t("Stdout alınamadı.")))));
     }
 }
-fn run_easy_install_script(sender: mpsc::Sender<TestMsg>, cancel_flag: Arc<AtomicBool>) {
+fn run_easy_install_script(sender: mpsc::Sender<TestMsg>, cancel_flag: Arc<AtomicBool>, preserve_config: bool) {
     let zapret_dir = get_zapret_path();
     let install_script = zapret_dir.join("install_easy.sh");
     if !install_script.exists() {
@@ -1944,7 +2845,9 @@ fn run_easy_install_script(sender: mpsc::Sender<TestMsg>, cancel_flag: Arc<Atomi
     }
     let _ = Command::new("chmod").arg("+x").arg(wrapper_path).output();
     let init_system = get_init_system();
+    let fwtype = firewall::resolve_backend(&get_distro_id()).fwtype();
     let mut post_install_cmds = String::from("sed -i 's/^NFQWS_ENABLE=.*/NFQWS_ENABLE=1/' /opt/zapret/config\n");
+    post_install_cmds.push_str(&format!("if grep -q '^#\\?FWTYPE=' /opt/zapret/config; then sed -i 's/^#\\?FWTYPE=.*/FWTYPE={}/' /opt/zapret/config; else echo 'FWTYPE={}' >> /opt/zapret/config; fi\n", fwtype, fwtype));
     if init_system == "runit" {
         post_install_cmds.push_str("if [ -d \"/opt/zapret/init.d/runit/zapret\" ]; then\n");
         post_install_cmds.push_str("  mkdir -p /etc/sv/zapret\n");
@@ -1954,9 +2857,29 @@ fn run_easy_install_script(sender: mpsc::Sender<TestMsg>, cancel_flag: Arc<Atomi
         post_install_cmds.push_str("  sv up zapret\n");
         post_install_cmds.push_str("fi\n");
     }
+    let mut backup_cmds = String::new();
+    if preserve_config {
+        let backup_dir = "/tmp/zapret-gtk-upgrade";
+        backup_cmds.push_str(&format!("rm -rf {0} && mkdir -p {0}/ipset\n", backup_dir));
+        backup_cmds.push_str(&format!("cp -a /opt/zapret/config {}/config 2>/dev/null || true\n", backup_dir));
+        backup_cmds.push_str(&format!("cp -a {}/*.txt {}/ipset/ 2>/dev/null || true\n", nfqws::HOSTLIST_DIR, backup_dir));
+        let restore = format!(
+            "if [ -f {0}/config ]; then cp -a {0}/config /opt/zapret/config; fi\ncp -a {0}/ipset/*.txt {1}/ 2>/dev/null || true\nrm -rf {0}\n",
+            backup_dir,
+            nfqws::HOSTLIST_DIR
+        );
+        post_install_cmds.insert_str(0, &restore);
+        post_install_cmds.push_str(match init_system.as_str() {
+            "openrc" => "rc-service zapret restart || true\n",
+            "runit" => "sv restart zapret || true\n",
+            _ => "systemctl restart zapret || true\n",
+        });
+    }
     let wrapper_content_fixed = format!(
-        "#!/bin/sh\nexport ZAPRET_BASE=\"{}\"\n\"{}\" < \"{}\"\nexit_code=$?\nif [ $exit_code -eq 0 ]; then\n{}\nfi\nexit $exit_code\n", 
+        "#!/bin/sh\nexport ZAPRET_BASE=\"{}\"\nexport FWTYPE=\"{}\"\n{}\"{}\" < \"{}\"\nexit_code=$?\nif [ $exit_code -eq 0 ]; then\n{}\nfi\nexit $exit_code\n", 
         zapret_base_str, 
+        fwtype, 
+        backup_cmds,
         install_script.to_string_lossy(), 
         input_path.to_string_lossy(),
         post_install_cmds
@@ -1994,6 +2917,9 @@ fn run_easy_install_script(sender: mpsc::Sender<TestMsg>, cancel_flag: Arc<Atomi
     let status = child.wait();
     match status {
         Ok(s) if s.success() => {
+             if let Err(e) = version::record_installed(&zapret_dir) {
+                 log_to_file(&format!("Could not record zapret version: {}", e));
+             }
              let _ = sender.send(TestMsg::InstallFinished(Ok(())));
         },
         Ok(s) => {
@@ -2019,59 +2945,1194 @@ fn save_strategies_to_json(strategies: &Vec<String>) -> io::Result<()> {
     writeln!(file, "]")?;
     Ok(())
 }
-fn add_entry_row(container: &Box, grab_focus: bool) {
-    let entry = Entry::builder()
-        .placeholder_text("Veri girin...")
+fn append_strategy_row(list_box: &ListBox, strategy: &str, active: bool, scores: &[benchmark::BenchmarkResult]) {
+    let child_label = Label::builder()
+        .label(strategy)
+        .wrap(true)
+        .max_width_chars(50)
+        .xalign(0.0)
         .build();
-    let container_clone = container.clone();
-    entry.connect_activate(move |_| {
-        add_entry_row(&container_clone, true);
+    let check = CheckButton::builder()
+        .child(&child_label)
+        .active(active)
+        .hexpand(true)
+        .build();
+    let domains_button = Button::builder()
+        .icon_name("network-server-symbolic")
+        .css_classes(vec!["flat"])
+        .valign(gtk::Align::Center)
+        .build();
+    update_domains_button(&domains_button, strategy);
+    let strategy_owned = strategy.to_string();
+    domains_button.connect_clicked(move |b| {
+        show_strategy_domains_dialog(b, &strategy_owned);
     });
-    container.append(&entry);
-    if grab_focus {
-        entry.grab_focus();
-    }
-}
-fn check_processes() -> Vec<String> {
-    let to_check = vec![
-        "tpws", 
-        "nfqws", 
-        "dvtws", 
-        "winws", 
-        "goodbyedpi", 
-        "openvpn", 
-        "wireguard", 
-        "zapret",
-        "warp-svc"
-    ];
-    let mut found = Vec::new();
-    for proc in to_check {
-        let output = Command::new("pgrep")
-            .arg("-x")
-            .arg(proc)
-            .output();
-        if let Ok(out) = output {
-            if out.status.success() {
-                found.push(proc.to_string());
-            }
-        }
-    }
-    found
-}
-fn check_network_manager() -> bool {
-    if Command::new("which").arg("nmcli").output().is_err() {
-        return false;
-    }
-    if let Ok(output) = Command::new("nmcli").arg("general").arg("status").output() {
-        if output.status.success() {
-             let _out = String::from_utf8_lossy(&output.stdout);
-             return true;
-        }
-    }
-    false
-}
-
-fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label, dns_label: Label, overwrite: bool, is_complete_flag: Rc<Cell<bool>>, is_installing_flag: Rc<Cell<bool>>, pid_store: Arc<Mutex<Option<u32>>>, cancel_flag: Arc<AtomicBool>, cancel_flag_ui: Arc<AtomicBool>, set_dns: bool) {
+    let edit_button = Button::builder()
+        .icon_name("document-edit-symbolic")
+        .tooltip_text(&t("Düzenle"))
+        .css_classes(vec!["flat"])
+        .valign(gtk::Align::Center)
+        .build();
+    let strategy_edit = strategy.to_string();
+    edit_button.connect_clicked(move |b| {
+        let list_box = b.ancestor(ListBox::static_type()).and_then(|w| w.downcast::<ListBox>().ok());
+        editor::show_strategy_editor(b, &strategy_edit, move |new_strategy| {
+            let mut strategies = load_saved_strategies();
+            if strategies.contains(&new_strategy) {
+                return;
+            }
+            strategies.push(new_strategy.clone());
+            if let Err(e) = save_strategies_to_json(&strategies) {
+                log_to_file(&format!("Could not save edited strategy: {}", e));
+                return;
+            }
+            log_to_file(&format!("Edited strategy saved: {}", new_strategy));
+            if let Some(list_box) = &list_box {
+                append_strategy_row(list_box, &new_strategy, false, &[]);
+            }
+        });
+    });
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(10)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_start(10)
+        .margin_end(10)
+        .build();
+    row_box.append(&check);
+    if let Some(result) = scores.iter().find(|r| r.strategy == strategy) {
+        let score_label = Label::builder()
+            .label(format!("{:.0}", result.score))
+            .tooltip_text(t("Hız testi puanı\nTLS el sıkışma: {h} ms\nİlk bayt: {f} ms\nHız: {v} KB/s")
+                .replace("{h}", &format!("{:.0}", result.handshake_ms))
+                .replace("{f}", &format!("{:.0}", result.ttfb_ms))
+                .replace("{v}", &format!("{:.0}", result.speed / 1024.0)))
+            .css_classes(vec!["dim-label", "caption"])
+            .valign(gtk::Align::Center)
+            .build();
+        row_box.append(&score_label);
+    }
+    row_box.append(&edit_button);
+    row_box.append(&domains_button);
+    list_box.append(&row_box);
+}
+fn populate_strategy_list(list_box: &ListBox, strategies: &[String], active: &[String]) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+    let scores = benchmark::load_scores();
+    for strategy in strategies {
+        append_strategy_row(list_box, strategy, active.contains(strategy), &scores);
+    }
+}
+fn strategy_row_check(widget: &gtk::Widget) -> Option<CheckButton> {
+    let content = if let Ok(row) = widget.clone().downcast::<ListBoxRow>() {
+        row.child()?
+    } else {
+        widget.clone()
+    };
+    if let Ok(check) = content.clone().downcast::<CheckButton>() {
+        return Some(check);
+    }
+    content.first_child()?.downcast::<CheckButton>().ok()
+}
+fn strategy_check_text(check: &CheckButton) -> Option<String> {
+    if let Some(lbl) = check.label() {
+        return Some(lbl.to_string());
+    }
+    check.child()?.downcast::<Label>().ok().map(|lbl| lbl.label().to_string())
+}
+fn collect_selected_strategies(list_box: &ListBox) -> Vec<String> {
+    let mut selected = Vec::new();
+    let mut child = list_box.first_child();
+    while let Some(widget) = child {
+        if let Some(check) = strategy_row_check(&widget) {
+            if check.is_active() {
+                if let Some(text) = strategy_check_text(&check) {
+                    selected.push(text);
+                }
+            }
+        }
+        child = widget.next_sibling();
+    }
+    selected
+}
+fn update_domains_button(button: &Button, strategy: &str) {
+    let mapped = domains::domains_for_strategy(&domains::load_strategy_domains(), strategy);
+    if mapped.is_empty() {
+        button.set_tooltip_text(Some(&t("Alan adı atanmadı (varsayılan profil)")));
+        button.remove_css_class("accent");
+    } else {
+        button.set_tooltip_text(Some(&t("Atanan alan adları: {}").replace("{}", &mapped.join(", "))));
+        button.add_css_class("accent");
+    }
+}
+fn refresh_dns_backup_row(row: &adw::ActionRow, restore_btn: &Button) {
+    let backups = dns::load_backups();
+    let system_changes = dns::system_changes();
+    restore_btn.set_sensitive(!backups.is_empty() || !system_changes.is_empty());
+    if backups.is_empty() && system_changes.is_empty() {
+        row.set_subtitle(&t("Kayıtlı orijinal DNS ayarı yok."));
+        return;
+    }
+    let mut lines: Vec<String> = backups
+        .iter()
+        .map(|b| {
+            let servers = if b.ipv4_dns.is_empty() { t("otomatik") } else { b.ipv4_dns.clone() };
+            format!("{}: {}", b.name, servers)
+        })
+        .collect();
+    lines.extend(system_changes);
+    row.set_subtitle(&lines.join("\n"));
+}
+fn populate_dns_connections(group: &adw::PreferencesGroup, rows: &Rc<RefCell<Vec<gtk::Widget>>>, transport: dns::Transport) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+    let backend = dns::resolve_backend(transport);
+    let nm_managed = backend == dns::Backend::NetworkManager || (backend == dns::Backend::Resolved && dns::network_manager_running());
+    if !nm_managed {
+        let row = adw::ActionRow::builder()
+            .title(&t("NetworkManager kullanılmıyor"))
+            .subtitle(&t("DNS {} ile sistem genelinde ayarlanır.").replace("{}", backend.display_name()))
+            .build();
+        group.add(&row);
+        rows.borrow_mut().push(row.upcast());
+        return;
+    }
+    let connections = dns::candidate_connections();
+    if connections.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(&t("NetworkManager bağlantısı bulunamadı"))
+            .build();
+        group.add(&row);
+        rows.borrow_mut().push(row.upcast());
+        return;
+    }
+    let switches: Rc<RefCell<Vec<(String, adw::SwitchRow)>>> = Rc::new(RefCell::new(Vec::new()));
+    for connection in &connections {
+        let mut details = vec![connection.kind.clone()];
+        if !connection.device.is_empty() {
+            details.push(connection.device.clone());
+        }
+        if connection.active {
+            details.push(t("etkin"));
+        }
+        if connection.autoconnect {
+            details.push(t("otomatik bağlanır"));
+        }
+        let row = adw::SwitchRow::builder()
+            .title(&connection.name)
+            .subtitle(&details.join(" · "))
+            .active(dns::is_selected(connection))
+            .build();
+        let switches_toggle = switches.clone();
+        row.connect_active_notify(move |_| {
+            let selected: Vec<String> = switches_toggle
+                .borrow()
+                .iter()
+                .filter(|(_, r)| r.is_active())
+                .map(|(uuid, _)| uuid.clone())
+                .collect();
+            if let Err(e) = dns::set_selected_uuids(&selected) {
+                log_to_file(&format!("Could not save DNS connection selection: {}", e));
+            }
+        });
+        group.add(&row);
+        switches.borrow_mut().push((connection.uuid.clone(), row.clone()));
+        rows.borrow_mut().push(row.upcast());
+    }
+}
+fn confirm_pending_release(anchor: &Button) {
+    let Some(entry) = integrity::pending() else { return };
+    let dialog = adw::MessageDialog::builder()
+        .heading(&t("Onaylanmamış Sürüm"))
+        .body(&t("{n} daha önce onaylanmadı.\n\n{k}: {v}\n\nBu değeri zapret'in sürüm sayfasıyla karşılaştırdıysanız güvenebilirsiniz; ardından kurulumu yeniden deneyin.")
+            .replace("{n}", &entry.name)
+            .replace("{k}", &entry.kind)
+            .replace("{v}", &entry.value))
+        .build();
+    dialog.add_response("cancel", &t("İptal"));
+    dialog.add_response("trust", &t("Güven"));
+    dialog.set_response_appearance("trust", ResponseAppearance::Destructive);
+    if let Some(win) = anchor.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        dialog.set_transient_for(Some(&win));
+    }
+    dialog.connect_response(None, |d, response| {
+        d.close();
+        if response == "trust" {
+            if let Err(e) = integrity::trust_pending() {
+                log_to_file(&format!("Could not record confirmed release: {}", e));
+            }
+        }
+    });
+    dialog.present();
+}
+fn show_dns_check_report(anchor: &Button, report: &dnscheck::Report) {
+    let problems = report.problems();
+    let (heading, body) = if !report.verified() {
+        (t("Doğrulanamadı"), t("Güvenilir DoH sunucusuna ulaşılamadı; yanıtlar karşılaştırılamadı."))
+    } else if problems.is_empty() {
+        (t("Sorun Bulunamadı"), t("Sistem DNS'i doğru yanıt veriyor."))
+    } else {
+        (t("DNS Sorunu Bulundu"), problems.join("\n\n"))
+    };
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(vec!["boxed-list"])
+        .build();
+    for check in &report.checks {
+        let answers = |addresses: &[String]| if addresses.is_empty() { "—".to_string() } else { addresses.join(", ") };
+        let row = adw::ActionRow::builder()
+            .title(&check.domain)
+            .subtitle(&t("Sistem: {s}\nDoH: {d}").replace("{s}", &answers(&check.system)).replace("{d}", &answers(&check.trusted)))
+            .subtitle_lines(0)
+            .build();
+        let verdict = Label::builder()
+            .label(&check.verdict.label())
+            .css_classes(vec![if check.verdict.is_problem() { "error" } else { "dim-label" }])
+            .build();
+        row.add_suffix(&verdict);
+        list.append(&row);
+    }
+    if !report.nameservers.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(&t("Kullanılan DNS Sunucuları"))
+            .subtitle(&report.nameservers.join(", "))
+            .build();
+        list.append(&row);
+    }
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(250)
+        .child(&list)
+        .build();
+    let dialog = adw::MessageDialog::builder()
+        .heading(&heading)
+        .body(&body)
+        .extra_child(&scrolled)
+        .build();
+    if let Some(win) = anchor.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        dialog.set_transient_for(Some(&win));
+    }
+    dialog.add_response("ok", &t("Tamam"));
+    dialog.present();
+}
+fn build_dns_page(summary_row: &adw::ActionRow) -> NavigationPage {
+    let config = dns::load();
+    let content = Box::new(Orientation::Vertical, 0);
+    let group = adw::PreferencesGroup::builder()
+        .title(&t("DNS Sağlayıcısı"))
+        .description(&t("DoH ve DNSCrypt seçeneklerinde sorgular dnscrypt-proxy üzerinden şifreli gönderilir ve sistem DNS'i 127.0.0.1 olarak ayarlanır."))
+        .margin_top(20)
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let provider_names: Vec<String> = dns::PROVIDERS.iter().map(|p| p.display_name()).collect();
+    let provider_refs: Vec<&str> = provider_names.iter().map(|s| s.as_str()).collect();
+    let provider_row = adw::ComboRow::builder()
+        .title(&t("Sağlayıcı"))
+        .model(&gtk::StringList::new(&provider_refs))
+        .selected(dns::PROVIDERS.iter().position(|p| *p == config.provider).unwrap_or(0) as u32)
+        .build();
+    group.add(&provider_row);
+    let transport_names: Vec<String> = dns::TRANSPORTS.iter().map(|tr| tr.display_name()).collect();
+    let transport_refs: Vec<&str> = transport_names.iter().map(|s| s.as_str()).collect();
+    let transport_row = adw::ComboRow::builder()
+        .title(&t("Aktarım"))
+        .model(&gtk::StringList::new(&transport_refs))
+        .selected(dns::TRANSPORTS.iter().position(|tr| *tr == config.transport).unwrap_or(0) as u32)
+        .build();
+    group.add(&transport_row);
+    let custom_row = adw::EntryRow::builder()
+        .title(&t("Özel Sunucular"))
+        .text(&config.custom)
+        .visible(config.provider == dns::Provider::Custom)
+        .build();
+    custom_row.set_tooltip_text(Some(&t("Düz DNS için IP adresleri, DoT için adres#sunucu-adı, DoH/DNSCrypt için sdns:// damgaları (boşlukla ayrılmış)")));
+    group.add(&custom_row);
+    let backend_model = gtk::StringList::new(&[&t("Otomatik"), "NetworkManager", "systemd-resolved", "/etc/resolv.conf"]);
+    let backend_row = adw::ComboRow::builder()
+        .title(&t("Arka Uç"))
+        .subtitle(&dns::describe_backend(config.transport))
+        .model(&backend_model)
+        .build();
+    let current_backend = dns::backend_override();
+    if let Some(index) = dns::BACKEND_CHOICES.iter().position(|c| *c == current_backend) {
+        backend_row.set_selected(index as u32);
+    }
+    group.add(&backend_row);
+    content.append(&group);
+    let connections_group = adw::PreferencesGroup::builder()
+        .title(&t("Bağlantılar"))
+        .description(&t("DNS, seçili bağlantıların IPv4 ve IPv6 ayarlarına yazılır. Etkin ve otomatik bağlanan bağlantılar listelenir."))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    content.append(&connections_group);
+    let connection_rows: Rc<RefCell<Vec<gtk::Widget>>> = Rc::new(RefCell::new(Vec::new()));
+    populate_dns_connections(&connections_group, &connection_rows, config.transport);
+
+    let message_label = Label::builder()
+        .wrap(true)
+        .max_width_chars(40)
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .css_classes(vec!["error"])
+        .visible(false)
+        .build();
+    content.append(&message_label);
+    let apply_btn = Button::builder()
+        .label(&t("Uygula"))
+        .css_classes(vec!["suggested-action", "pill"])
+        .halign(gtk::Align::Center)
+        .margin_top(10)
+        .margin_bottom(20)
+        .build();
+    content.append(&apply_btn);
+    let restore_group = adw::PreferencesGroup::builder()
+        .title(&t("Orijinal DNS"))
+        .description(&t("Bağlantıların değiştirilmeden önceki DNS ayarları kaydedilir. Zapret silinirken otomatik olarak geri yüklenir."))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(20)
+        .build();
+    let restore_row = adw::ActionRow::builder()
+        .title(&t("Kayıtlı Bağlantılar"))
+        .subtitle_lines(0)
+        .build();
+    let restore_btn = Button::builder()
+        .label(&t("Orijinal DNS'i Geri Yükle"))
+        .valign(gtk::Align::Center)
+        .build();
+    restore_row.add_suffix(&restore_btn);
+    restore_group.add(&restore_row);
+    content.append(&restore_group);
+    let check_group = adw::PreferencesGroup::builder()
+        .title(&t("Tanılama"))
+        .description(&format!("{} {}", t("Alan adlarınız sistem DNS'i ve güvenilir bir DoH sunucusu üzerinden çözülüp karşılaştırılır. Sahte veya engellenmiş yanıtlar ve dnscrypt-proxy'yi atlayan sorgular raporlanır."), t("Test yalnızca siz başlattığınızda çalışır ve alan adlarınızı Cloudflare ile Google'ın DoH sunucularına gönderir.")))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(20)
+        .build();
+    let check_row = adw::ActionRow::builder()
+        .title(&t("DNS Zehirlenme ve Sızıntı Testi"))
+        .build();
+    let check_btn = Button::builder()
+        .label(&t("Denetle"))
+        .valign(gtk::Align::Center)
+        .build();
+    check_row.add_suffix(&check_btn);
+    check_group.add(&check_row);
+    content.append(&check_group);
+    check_btn.connect_clicked(|btn| {
+        btn.set_sensitive(false);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(dnscheck::run());
+        });
+        let btn_timer = btn.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(report) => {
+                    btn_timer.set_sensitive(true);
+                    show_dns_check_report(&btn_timer, &report);
+                    glib::ControlFlow::Break
+                },
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
+    refresh_dns_backup_row(&restore_row, &restore_btn);
+    let restore_row_click = restore_row.clone();
+    restore_btn.connect_clicked(move |btn| {
+        btn.set_sensitive(false);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(dns::restore());
+        });
+        let btn_timer = btn.clone();
+        let row_timer = restore_row_click.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    refresh_dns_backup_row(&row_timer, &btn_timer);
+                    let (heading, body) = match result {
+                        Ok(()) => (t("DNS Geri Yüklendi"), t("Bağlantıların orijinal DNS ayarları geri yüklendi.")),
+                        Err(e) => {
+                            log_to_file(&format!("DNS restore failed: {}", e));
+                            (t("Hata"), e)
+                        }
+                    };
+                    let dialog = adw::MessageDialog::builder()
+                        .heading(&heading)
+                        .body(&body)
+                        .build();
+                    if let Some(win) = btn_timer.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+                        dialog.set_transient_for(Some(&win));
+                    }
+                    dialog.add_response("ok", &t("Tamam"));
+                    dialog.present();
+                    glib::ControlFlow::Break
+                },
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
+    let read_config = {
+        let provider_row = provider_row.clone();
+        let transport_row = transport_row.clone();
+        let custom_row = custom_row.clone();
+        move || dns::DnsConfig {
+            provider: dns::PROVIDERS[(provider_row.selected() as usize).min(dns::PROVIDERS.len() - 1)],
+            transport: dns::TRANSPORTS[(transport_row.selected() as usize).min(dns::TRANSPORTS.len() - 1)],
+            custom: custom_row.text().to_string(),
+        }
+    };
+    let validate = {
+        let read_config = read_config.clone();
+        let message_label = message_label.clone();
+        let custom_row = custom_row.clone();
+        move || {
+            let config = read_config();
+            custom_row.set_visible(config.provider == dns::Provider::Custom);
+            match config.validate() {
+                Ok(()) => message_label.set_visible(false),
+                Err(e) => {
+                    message_label.set_label(&e);
+                    message_label.set_visible(true);
+                }
+            }
+        }
+    };
+    let validate_provider = validate.clone();
+    provider_row.connect_selected_notify(move |_| validate_provider());
+    let refresh_backend = {
+        let read_config = read_config.clone();
+        let backend_row = backend_row.clone();
+        let connections_group = connections_group.clone();
+        let connection_rows = connection_rows.clone();
+        let validate = validate.clone();
+        move || {
+            let transport = read_config().transport;
+            backend_row.set_subtitle(&dns::describe_backend(transport));
+            populate_dns_connections(&connections_group, &connection_rows, transport);
+            validate();
+        }
+    };
+    let refresh_transport = refresh_backend.clone();
+    transport_row.connect_selected_notify(move |_| refresh_transport());
+    backend_row.connect_selected_notify(move |row| {
+        let choice = dns::BACKEND_CHOICES.get(row.selected() as usize).copied().unwrap_or("auto");
+        if let Err(e) = dns::set_backend_override(choice) {
+            log_to_file(&format!("Could not save DNS backend: {}", e));
+        }
+        refresh_backend();
+    });
+    let validate_custom = validate.clone();
+    custom_row.connect_changed(move |_| validate_custom());
+    validate();
+    let summary = summary_row.clone();
+    let restore_row_apply = restore_row.clone();
+    let restore_btn_apply = restore_btn.clone();
+    apply_btn.connect_clicked(move |btn| {
+        let config = read_config();
+        if let Err(e) = dns::save(&config) {
+            log_to_file(&format!("Could not save DNS settings: {}", e));
+        }
+        summary.set_subtitle(&config.describe());
+        btn.set_sensitive(false);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(dns::apply(&config));
+        });
+        let btn_timer = btn.clone();
+        let restore_row_timer = restore_row_apply.clone();
+        let restore_btn_timer = restore_btn_apply.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    btn_timer.set_sensitive(true);
+                    refresh_dns_backup_row(&restore_row_timer, &restore_btn_timer);
+                    let (heading, body) = match result {
+                        Ok(results) => {
+                            let mut body = t("DNS ayarları uygulandı: {}").replace("{}", &dns::load().describe());
+                            if results.is_empty() {
+                                body.push_str("\n\n");
+                                body.push_str(&t("Seçili bağlantı yok; yalnızca dnscrypt-proxy yapılandırıldı."));
+                            }
+                            for result in &results {
+                                let mark = if result.ok { "✓" } else { "✗" };
+                                body.push_str(&format!("\n{} {}: {}", mark, result.name, result.message));
+                            }
+                            let heading = if results.iter().all(|r| r.ok) { t("DNS Ayarlandı") } else { t("DNS Kısmen Ayarlandı") };
+                            (heading, body)
+                        },
+                        Err(e) => {
+                            log_to_file(&format!("DNS apply failed: {}", e));
+                            (t("Hata"), e)
+                        }
+                    };
+                    let dialog = adw::MessageDialog::builder()
+                        .heading(&heading)
+                        .body(&body)
+                        .build();
+                    if let Some(win) = btn_timer.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+                        dialog.set_transient_for(Some(&win));
+                    }
+                    dialog.add_response("ok", &t("Tamam"));
+                    dialog.present();
+                    glib::ControlFlow::Break
+                },
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
+    let header = HeaderBar::builder()
+        .show_back_button(true)
+        .build();
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+    let view = ToolbarView::builder()
+        .content(&scrolled)
+        .build();
+    view.add_top_bar(&header);
+    let page = NavigationPage::builder()
+        .child(&view)
+        .title(&t("DNS Ayarları"))
+        .tag("dns_page")
+        .build();
+    page.connect_showing(move |_| populate_dns_connections(&connections_group, &connection_rows, dns::load().transport));
+    page
+}
+fn show_rule_inspector(window: &ApplicationWindow) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(rules::inspect());
+    });
+    let win = window.clone();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+        };
+        let queue_rules = match result {
+            Ok(queue_rules) => queue_rules,
+            Err(e) => {
+                log_to_file(&format!("Could not read firewall rules: {}", e));
+                let dialog = adw::MessageDialog::builder()
+                    .transient_for(&win)
+                    .heading(&t("Hata"))
+                    .body(&e.to_string())
+                    .build();
+                dialog.add_response("ok", &t("Tamam"));
+                dialog.present();
+                return glib::ControlFlow::Break;
+            }
+        };
+        let (is_active, _) = get_service_status();
+        let qnum = rules::configured_qnum();
+        let problems = rules::find_problems(&queue_rules, is_active, qnum);
+        let content = Box::new(Orientation::Vertical, 10);
+        for problem in &problems {
+            let label = Label::builder()
+                .label(problem)
+                .wrap(true)
+                .xalign(0.0)
+                .css_classes(vec!["error"])
+                .build();
+            content.append(&label);
+        }
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(vec!["boxed-list"])
+            .build();
+        for rule in &queue_rules {
+            let mut details = vec![rule.location()];
+            if !rule.ports.is_empty() {
+                details.push(t("Portlar: {}").replace("{}", &rule.ports));
+            }
+            if !rule.sets.is_empty() {
+                details.push(t("ipset: {}").replace("{}", &rule.sets.join(", ")));
+            }
+            details.push(match (rule.packets, rule.bytes) {
+                (Some(packets), Some(bytes)) => t("{p} paket, {b}")
+                    .replace("{p}", &packets.to_string())
+                    .replace("{b}", &rules::format_bytes(bytes)),
+                _ => t("Sayaç yok"),
+            });
+            let mut title = t("NFQUEUE {}").replace("{}", &rule.queue);
+            if rule.is_zapret(qnum) {
+                title = t("{} (zapret)").replace("{}", &title);
+            }
+            let row = adw::ActionRow::builder()
+                .title(&title)
+                .subtitle(&details.join("\n"))
+                .build();
+            row.set_tooltip_text(Some(&rule.spec));
+            list.append(&row);
+        }
+        if queue_rules.is_empty() {
+            list.append(&adw::ActionRow::builder().title(&t("Hiç NFQUEUE kuralı bulunamadı.")).build());
+        }
+        content.append(&list);
+        let scrolled = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .min_content_height(300)
+            .child(&content)
+            .build();
+        let body = if problems.is_empty() {
+            t("Kurallar servis durumuyla uyumlu.")
+        } else {
+            t("Kurallarda sorun bulundu.")
+        };
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&win)
+            .heading(&t("Güvenlik Duvarı Kuralları"))
+            .body(&body)
+            .extra_child(&scrolled)
+            .build();
+        dialog.add_response("refresh", &t("Yenile"));
+        dialog.add_response("ok", &t("Kapat"));
+        let win_refresh = win.clone();
+        dialog.connect_response(None, move |d, response| {
+            d.close();
+            if response == "refresh" {
+                show_rule_inspector(&win_refresh);
+            }
+        });
+        dialog.present();
+        glib::ControlFlow::Break
+    });
+}
+fn show_strategy_domains_dialog(button: &Button, strategy: &str) {
+    let mapping = domains::load_strategy_domains();
+    let tested = domains::domains_for_strategy(&domains::load_tested_domains(), strategy);
+    let mut candidates = tested.clone();
+    for domain in domains::load_domains().into_iter().chain(mapping.iter().map(|(d, _)| d.clone())) {
+        if !candidates.contains(&domain) {
+            candidates.push(domain);
+        }
+    }
+    if candidates.is_empty() {
+        let dialog = adw::MessageDialog::builder()
+            .heading(&t("Alan Adları"))
+            .body(&t("Henüz test edilmiş bir alan adı yok. Önce strateji araması yapın."))
+            .build();
+        if let Some(win) = button.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            dialog.set_transient_for(Some(&win));
+        }
+        dialog.add_response("ok", &t("Tamam"));
+        dialog.present();
+        return;
+    }
+    let list = Box::new(Orientation::Vertical, 5);
+    let mut checks = Vec::new();
+    for domain in &candidates {
+        let owner = mapping.iter().find(|(d, _)| d == domain).map(|(_, s)| s.clone());
+        let mut label = domain.clone();
+        if tested.contains(domain) {
+            label = t("{} (test edildi)").replace("{}", domain);
+        }
+        let check = CheckButton::builder()
+            .label(&label)
+            .active(owner.as_deref() == Some(strategy))
+            .build();
+        if let Some(other) = owner.filter(|o| o != strategy) {
+            check.set_tooltip_text(Some(&t("Şu anda başka bir stratejiye atanmış: {}").replace("{}", &other)));
+        }
+        list.append(&check);
+        checks.push(check);
+    }
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(200)
+        .child(&list)
+        .build();
+    let dialog = adw::MessageDialog::builder()
+        .heading(&t("Alan Adları"))
+        .body(&t("Bu stratejinin kullanılacağı alan adlarını seçin. Atanmamış alan adları varsayılan profili kullanır."))
+        .extra_child(&scrolled)
+        .build();
+    if let Some(win) = button.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        dialog.set_transient_for(Some(&win));
+    }
+    dialog.add_response("cancel", &t("İptal"));
+    dialog.add_response("save", &t("Kaydet"));
+    dialog.set_response_appearance("save", ResponseAppearance::Suggested);
+    let strategy_owned = strategy.to_string();
+    let button_clone = button.clone();
+    dialog.connect_response(None, move |d, response| {
+        d.close();
+        if response != "save" {
+            return;
+        }
+        let mut mapping = domains::load_strategy_domains();
+        for (check, domain) in checks.iter().zip(candidates.iter()) {
+            let current = mapping.iter().position(|(d, _)| d == domain);
+            if check.is_active() {
+                match current {
+                    Some(idx) => mapping[idx].1 = strategy_owned.clone(),
+                    None => mapping.push((domain.clone(), strategy_owned.clone())),
+                }
+            } else if let Some(idx) = current {
+                if mapping[idx].1 == strategy_owned {
+                    mapping.remove(idx);
+                }
+            }
+        }
+        if let Err(e) = domains::save_strategy_domains(&mapping) {
+            log_to_file(&format!("Could not save strategy domains: {}", e));
+        }
+        update_domains_button(&button_clone, &strategy_owned);
+    });
+    dialog.present();
+}
+fn add_entry_row(container: &Box, grab_focus: bool) -> Entry {
+    let entry = Entry::builder()
+        .placeholder_text("Veri girin...")
+        .build();
+    let container_clone = container.clone();
+    entry.connect_activate(move |_| {
+        add_entry_row(&container_clone, true);
+    });
+    let container_changed = container.clone();
+    entry.connect_changed(move |e| {
+        if domains::has_multiple_domains(&e.text()) {
+            let e = e.clone();
+            let container = container_changed.clone();
+            glib::idle_add_local_once(move || {
+                split_entry_domains(&container, &e);
+            });
+        } else {
+            mark_entry_validity(e);
+        }
+    });
+    container.append(&entry);
+    if grab_focus {
+        entry.grab_focus();
+    }
+    entry
+}
+fn split_entry_domains(container: &Box, entry: &Entry) {
+    let parts = domains::split_domain_input(&entry.text());
+    let mut existing = Vec::new();
+    let mut child = container.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if let Ok(other) = widget.downcast::<Entry>() {
+            if &other != entry {
+                existing.push(domains::normalize_domain(&other.text()).unwrap_or_else(|_| other.text().to_string()));
+            }
+        }
+    }
+    let mut previous = entry.clone();
+    let mut first = true;
+    for part in parts {
+        let key = domains::normalize_domain(&part).unwrap_or_else(|_| part.clone());
+        if existing.contains(&key) {
+            continue;
+        }
+        existing.push(key);
+        if first {
+            entry.set_text(&part);
+            first = false;
+            continue;
+        }
+        let new_entry = add_entry_row(container, false);
+        new_entry.set_text(&part);
+        container.reorder_child_after(&new_entry, Some(&previous));
+        previous = new_entry;
+    }
+    if first {
+        entry.set_text("");
+    }
+}
+fn fill_domain_entries(container: &Box, new_domains: &[String]) {
+    let mut existing = Vec::new();
+    let mut empty_entries = Vec::new();
+    let mut child = container.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if let Ok(entry) = widget.downcast::<Entry>() {
+            let text = entry.text();
+            if text.trim().is_empty() {
+                empty_entries.push(entry);
+            } else {
+                existing.push(domains::normalize_domain(&text).unwrap_or_else(|_| text.to_string()));
+            }
+        }
+    }
+    let mut empty_entries = empty_entries.into_iter();
+    for domain in new_domains {
+        if existing.contains(domain) {
+            continue;
+        }
+        existing.push(domain.clone());
+        let entry = match empty_entries.next() {
+            Some(entry) => entry,
+            None => add_entry_row(container, false),
+        };
+        entry.set_text(domain);
+    }
+}
+fn show_domain_page(nav_view: &NavigationView, page: &NavigationPage) {
+    if nav_view.find_page("settings_page").is_some() {
+        nav_view.pop_to_tag("settings_page");
+    } else {
+        nav_view.push(page);
+    }
+}
+fn replace_domain_entries(container: &Box, new_domains: &[String]) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+    add_entry_row(container, false);
+    fill_domain_entries(container, new_domains);
+}
+fn mark_entry_validity(entry: &Entry) {
+    let text = entry.text();
+    let error = if text.trim().is_empty() {
+        None
+    } else {
+        domains::normalize_domain(&text).err()
+    };
+    match error {
+        Some(msg) => {
+            entry.add_css_class("error");
+            entry.set_secondary_icon_name(Some("dialog-warning-symbolic"));
+            entry.set_secondary_icon_tooltip_text(Some(&msg));
+        },
+        None => {
+            entry.remove_css_class("error");
+            entry.set_secondary_icon_name(None);
+            entry.set_secondary_icon_tooltip_text(None);
+        }
+    }
+}
+fn populate_conflict_list(list: &gtk::ListBox, summary: &Label, continue_btn: &Button, conflicts: &[conflicts::Conflict]) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    let blocking = conflicts.iter().filter(|c| c.blocks()).count();
+    if conflicts.is_empty() {
+        summary.set_label(&t("Tüm çakışmalar giderildi. Yapılan değişiklikleri Ayarlar sayfasından geri alabilirsiniz."));
+        if let Some(scrolled) = list.ancestor(ScrolledWindow::static_type()) {
+            scrolled.set_visible(false);
+        }
+    } else if blocking == 0 {
+        summary.set_label(&t("Engel oluşturan bir çakışma yok. Aşağıdakiler yalnızca bilgi amaçlıdır."));
+    } else {
+        summary.set_label(&t("Zapret ile aynı trafiği işleyen {} çakışma bulundu. Devam etmeden önce bunları kapatmanız önerilir.").replace("{}", &blocking.to_string()));
+    }
+    if blocking == 0 {
+        continue_btn.set_label(&t("Devam Et"));
+        continue_btn.remove_css_class("destructive-action");
+        continue_btn.add_css_class("suggested-action");
+    } else {
+        continue_btn.set_label(&t("Yine de Devam Et"));
+        continue_btn.remove_css_class("suggested-action");
+        continue_btn.add_css_class("destructive-action");
+    }
+    for conflict in conflicts {
+        let row = adw::ActionRow::builder()
+            .title(&conflict.title)
+            .subtitle(&conflict.explanation)
+            .subtitle_lines(0)
+            .build();
+        let severity = Label::builder()
+            .label(&conflict.severity.label())
+            .css_classes(vec!["caption", conflict.severity.css_class()])
+            .valign(gtk::Align::Center)
+            .build();
+        row.add_suffix(&severity);
+        if let Some(remedy) = conflict.remedy.clone() {
+            let fix_btn = Button::builder()
+                .label(&t("Düzelt"))
+                .tooltip_text(&remedy.label)
+                .valign(gtk::Align::Center)
+                .css_classes(vec!["flat"])
+                .build();
+            let list_fix = list.clone();
+            let summary_fix = summary.clone();
+            let continue_fix = continue_btn.clone();
+            fix_btn.connect_clicked(move |btn| {
+                btn.set_sensitive(false);
+                let (sender, receiver) = mpsc::channel();
+                let remedy_thread = remedy.clone();
+                thread::spawn(move || {
+                    let result = conflicts::apply(&remedy_thread);
+                    let _ = sender.send((result, conflicts::detect()));
+                });
+                let btn_timer = btn.clone();
+                let list_timer = list_fix.clone();
+                let summary_timer = summary_fix.clone();
+                let continue_timer = continue_fix.clone();
+                glib::timeout_add_local(Duration::from_millis(100), move || {
+                    match receiver.try_recv() {
+                        Ok((result, remaining)) => {
+                            if let Err(e) = result {
+                                log_to_file(&format!("Remediation failed: {}", e));
+                                btn_timer.set_sensitive(true);
+                                btn_timer.set_tooltip_text(Some(&t("Düzeltme başarısız: {}").replace("{}", &e.to_string())));
+                                btn_timer.add_css_class("error");
+                                return glib::ControlFlow::Break;
+                            }
+                            populate_conflict_list(&list_timer, &summary_timer, &continue_timer, &remaining);
+                            glib::ControlFlow::Break
+                        },
+                        Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+                    }
+                });
+            });
+            row.add_suffix(&fix_btn);
+        }
+        list.append(&row);
+    }
+}
+fn build_version_group() -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title(&t("Zapret Sürümü"))
+        .description(&t("Yükseltme, seçilen sürümü derleyip install_easy ile yeniden kurar; config ve host listeleri korunur."))
+        .margin_start(20)
+        .margin_end(20)
+        .margin_bottom(10)
+        .build();
+    let installed_row = adw::ActionRow::builder()
+        .title(&t("Kurulu Sürüm"))
+        .subtitle(&version::installed().map(|v| v.describe()).unwrap_or_else(|| t("bilinmiyor")))
+        .build();
+    group.add(&installed_row);
+    let remote_row = adw::EntryRow::builder()
+        .title(&t("Depo veya Ayna"))
+        .text(&version::remote())
+        .show_apply_button(true)
+        .build();
+    remote_row.set_tooltip_text(Some(&t("Git adresi, github.com yavaşsa bir ayna veya yerel (bare) depo yolu")));
+    remote_row.connect_apply(|row| {
+        if let Err(e) = version::set_remote(&row.text()) {
+            log_to_file(&format!("Could not save zapret remote: {}", e));
+        }
+        row.set_text(&version::remote());
+    });
+    group.add(&remote_row);
+    let proxy_row = adw::EntryRow::builder()
+        .title(&t("Vekil Sunucu (boş: doğrudan)"))
+        .text(&version::proxy().unwrap_or_default())
+        .show_apply_button(true)
+        .build();
+    proxy_row.set_tooltip_text(Some(&t("git ve curl için HTTP veya SOCKS vekil sunucusu, ör. socks5h://127.0.0.1:9050")));
+    proxy_row.connect_apply(|row| match version::set_proxy(&row.text()) {
+        Ok(()) => {
+            row.remove_css_class("error");
+            row.set_tooltip_text(Some(&t("git ve curl için HTTP veya SOCKS vekil sunucusu, ör. socks5h://127.0.0.1:9050")));
+        }
+        Err(e) => {
+            row.add_css_class("error");
+            row.set_tooltip_text(Some(&e));
+        }
+    });
+    group.add(&proxy_row);
+    let pin_row = adw::EntryRow::builder()
+        .title(&t("Sabitlenen Sürüm (boş: en yeni)"))
+        .text(&version::pinned_tag().unwrap_or_default())
+        .show_apply_button(true)
+        .build();
+    pin_row.connect_apply(|row| {
+        if let Err(e) = version::set_pinned_tag(&row.text()) {
+            log_to_file(&format!("Could not save pinned zapret tag: {}", e));
+        }
+    });
+    group.add(&pin_row);
+    let verify_row = adw::SwitchRow::builder()
+        .title(&t("Kaynağı Doğrula"))
+        .subtitle(&t("İndirilen commit veya arşiv, ilk kurulumda onayladığınız değerle eşleşmezse kurulum durdurulur."))
+        .subtitle_lines(0)
+        .active(integrity::enabled())
+        .build();
+    verify_row.connect_active_notify(|row| {
+        if let Err(e) = integrity::set_enabled(row.is_active()) {
+            log_to_file(&format!("Could not save source verification setting: {}", e));
+        }
+    });
+    group.add(&verify_row);
+    let update_row = adw::ActionRow::builder()
+        .title(&t("Güncellemeler"))
+        .subtitle(&t("Denetlenmedi"))
+        .subtitle_lines(0)
+        .build();
+    let upgrade_btn = Button::builder()
+        .label(&t("Yükselt"))
+        .css_classes(vec!["suggested-action"])
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+    let check_btn = Button::builder()
+        .label(&t("Denetle"))
+        .valign(gtk::Align::Center)
+        .build();
+    update_row.add_suffix(&upgrade_btn);
+    update_row.add_suffix(&check_btn);
+    group.add(&update_row);
+    let target: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let update_row_check = update_row.clone();
+    let upgrade_btn_check = upgrade_btn.clone();
+    let target_check = target.clone();
+    check_btn.connect_clicked(move |btn| {
+        btn.set_sensitive(false);
+        upgrade_btn_check.set_visible(false);
+        update_row_check.set_subtitle(&t("Uzak depo denetleniyor..."));
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let remote = version::remote();
+            let _ = sender.send(version::check_reachable(&remote)
+                .and_then(|_| version::remote_tags(&remote))
+                .and_then(|tags| version::target_tag(&tags)));
+        });
+        let btn_timer = btn.clone();
+        let row = update_row_check.clone();
+        let upgrade_btn = upgrade_btn_check.clone();
+        let target = target_check.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    btn_timer.set_sensitive(true);
+                    match result {
+                        Ok(Some(tag)) => {
+                            let installed = version::installed();
+                            let outdated = version::needs_upgrade(installed.as_ref(), &tag);
+                            row.set_subtitle(&if outdated {
+                                t("{} kurulabilir.").replace("{}", &tag)
+                            } else {
+                                t("Güncel ({}).").replace("{}", &tag)
+                            });
+                            upgrade_btn.set_visible(outdated && Path::new("/opt/zapret").exists());
+                            target.replace(Some(tag));
+                        },
+                        Ok(None) => row.set_subtitle(&t("Uzak depoda sürüm etiketi yok.")),
+                        Err(e) => row.set_subtitle(&e),
+                    }
+                    glib::ControlFlow::Break
+                },
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
+    upgrade_btn.connect_clicked(move |btn| {
+        let Some(tag) = target.borrow().clone() else { return };
+        btn.set_sensitive(false);
+        check_btn.set_sensitive(false);
+        let (sender, receiver) = mpsc::channel();
+        let tag_thread = tag.clone();
+        thread::spawn(move || run_zapret_upgrade(&tag_thread, sender));
+        let btn_timer = btn.clone();
+        let check_btn = check_btn.clone();
+        let row = update_row.clone();
+        let installed_row = installed_row.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(TestMsg::Log(line)) => {
+                    row.set_subtitle(&line);
+                    glib::ControlFlow::Continue
+                },
+                Ok(TestMsg::InstallFinished(result)) => {
+                    btn_timer.set_sensitive(true);
+                    check_btn.set_sensitive(true);
+                    delete_local_zapret_folder();
+                    installed_row.set_subtitle(&version::installed().map(|v| v.describe()).unwrap_or_else(|| t("bilinmiyor")));
+                    match result {
+                        Ok(()) => {
+                            btn_timer.set_visible(false);
+                            row.set_subtitle(&t("{} sürümüne yükseltildi.").replace("{}", &tag));
+                        },
+                        Err(e) => {
+                            log_to_file(&format!("Zapret upgrade failed: {}", e));
+                            row.set_subtitle(&t("Yükseltme başarısız: {}").replace("{}", &e.to_string()));
+                            confirm_pending_release(&btn_timer);
+                        }
+                    }
+                    glib::ControlFlow::Break
+                },
+                Ok(_) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    });
+    group
+}
+fn run_zapret_upgrade(tag: &str, sender: mpsc::Sender<TestMsg>) {
+    let zapret_dir = get_zapret_path();
+    log_to_file(&format!("Upgrading zapret to {}", tag));
+    let prebuilt = source::prebuilt_mode();
+    let fetched = if prebuilt {
+        let _ = sender.send(TestMsg::Log(t("Zapret sürüm arşivi indiriliyor...")));
+        source::download_release(&version::remote(), tag, &zapret_dir)
+    } else {
+        let _ = sender.send(TestMsg::Log(t("Zapret deposu indiriliyor...")));
+        version::fetch_source(&version::remote(), Some(tag), &zapret_dir)
+    };
+    if let Err(e) = fetched {
+        let _ = sender.send(TestMsg::InstallFinished(Err(io::Error::other(e))));
+        return;
+    }
+    let built = if prebuilt {
+        let _ = sender.send(TestMsg::Log(t("Önceden derlenmiş ikili dosyalar hazırlanıyor...")));
+        source::prepare_binaries(&zapret_dir)
+    } else {
+        let _ = sender.send(TestMsg::Log(t("Zapret derleniyor (make)...")));
+        version::build(&zapret_dir)
+    };
+    if let Err(e) = built {
+        let _ = sender.send(TestMsg::InstallFinished(Err(io::Error::other(e))));
+        return;
+    }
+    let _ = sender.send(TestMsg::Log(t("Zapret Kuruluyor (/opt/zapret)...")));
+    run_easy_install_script(sender, Arc::new(AtomicBool::new(false)), true);
+}
+fn refresh_remediation_row(row: &adw::ActionRow, undo_btn: &Button) {
+    let records = conflicts::load_records();
+    undo_btn.set_sensitive(!records.is_empty());
+    if records.is_empty() {
+        row.set_subtitle(&t("Kayıtlı değişiklik yok"));
+    } else {
+        let descriptions: Vec<String> = records.into_iter().map(|r| r.description).collect();
+        row.set_subtitle(&descriptions.join("\n"));
+    }
+}
+// A failed tag listing only matters when a pin has to be honoured.
+fn resolve_zapret_tag(remote: &str) -> io::Result<Option<String>> {
+    match version::remote_tags(remote).and_then(|tags| version::target_tag(&tags)) {
+        Ok(tag) => Ok(tag),
+        Err(e) if version::pinned_tag().is_some() => Err(io::Error::other(e)),
+        Err(e) => {
+            log_to_file(&format!("Could not list zapret releases, using the default branch: {}", e));
+            Ok(None)
+        }
+    }
+}
+fn download_zapret_release(dest: &Path) -> io::Result<()> {
+    let remote = version::remote();
+    let Some(tag) = resolve_zapret_tag(&remote)? else {
+        return Err(io::Error::other(t("Uzak depoda sürüm etiketi yok.")));
+    };
+    log_to_file(&format!("Downloading zapret release {} from {}", tag, remote));
+    source::download_release(&remote, &tag, dest).map_err(io::Error::other)
+}
+fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label, dns_label: Label, overwrite: bool, is_complete_flag: Rc<Cell<bool>>, is_installing_flag: Rc<Cell<bool>>, pid_store: Arc<Mutex<Option<u32>>>, cancel_flag: Arc<AtomicBool>, cancel_flag_ui: Arc<AtomicBool>, set_dns: bool) {
     log_to_file(&format!("Installation command issued. Re-download: {}, Set DNS: {}", overwrite, set_dns));
     is_installing_flag.set(true);
     cancel_flag.store(false, Ordering::Relaxed);
@@ -2093,6 +4154,13 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
         let mut root_commands = String::from("#!/bin/sh\nset -e\nexec 2>&1\n");
         let mut needs_root_permission = false;
         let distro_id = get_distro_id();
+        let dns_config = dns::load();
+        if set_dns {
+            if let Err(e) = dns_config.validate() {
+                let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::InvalidInput, t("DNS ayarları geçersiz: {}").replace("{}", &e)))));
+                return;
+            }
+        }
         let zapret_full_path = get_zapret_path();
         let zapret_path_str = zapret_full_path.to_string_lossy().to_string();
         if cancel_flag_thread.load(Ordering::Relaxed) { return; }
@@ -2102,7 +4170,19 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
             needs_root_permission = true;
         }
         if cancel_flag_thread.load(Ordering::Relaxed) { return; }
-        let binary_deps = vec!["git", "curl", "ipset", "iptables", "make", "gcc", "dig", "dnscrypt-proxy"];
+        let backend = firewall::resolve_backend(&distro_id);
+        log_to_file(&format!("Firewall backend: {}", backend.fwtype()));
+        let mut binary_deps = vec!["git", "curl", "make", "gcc", "dig", "dnscrypt-proxy"];
+        if source::prebuilt_mode() {
+            binary_deps.retain(|dep| !matches!(*dep, "make" | "gcc"));
+        }
+        if let Some(local) = source::local_source() {
+            binary_deps.retain(|dep| *dep != "git");
+            if source::is_zip(&local) {
+                binary_deps.push("unzip");
+            }
+        }
+        binary_deps.extend_from_slice(backend.binary_deps());
         let mut dep_install_commands = Vec::new();
         for dep in binary_deps {
              let check = Command::new("which").arg(dep).output();
@@ -2117,7 +4197,7 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
                  }
              }
         }
-        let lib_deps = vec!["zlib", "libnetfilter_queue", "libmnl", "libcap"];
+        let lib_deps = if source::prebuilt_mode() { vec![] } else { source::BUILD_LIBS.to_vec() };
         for lib in lib_deps {
              let distro_pkg = get_distro_package_name(&distro_id, lib);
              if !is_package_installed(&distro_id, &distro_pkg) {
@@ -2160,38 +4240,36 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
         }
         if cancel_flag_thread.load(Ordering::Relaxed) { return; }
         root_commands.push_str("echo \"STATUS:CONFIGURING\"\n");
-        let config_file = "/etc/dnscrypt-proxy/dnscrypt-proxy.toml";
-        root_commands.push_str(&format!("if [ -f \"{}\" ]; then\n", config_file));
-        root_commands.push_str(&format!("  sed -i \"40s/^listen_addresses = \\['127\\.0\\.0\\.1:53'\\]$/listen_addresses = ['127.0.0.1:53', '[::1]:53']/\" {}\n", config_file));
-        root_commands.push_str("fi\n");
+        let mut configure_dnscrypt_later = false;
+        match dns::prepare_dnscrypt_config(&dns_config) {
+            Ok(Some(install)) => root_commands.push_str(&install),
+            Ok(None) => configure_dnscrypt_later = true,
+            Err(e) => log_to_file(&format!("Could not prepare dnscrypt-proxy config: {}", e)),
+        }
         if !needs_root_permission { needs_root_permission = true; }
         if set_dns {
             root_commands.push_str("echo \"STATUS:SETTING_DNS\"\n");
-            root_commands.push_str("if command -v nmcli >/dev/null 2>&1; then\n");
-            root_commands.push_str("  ACTIVE_CON=$(nmcli -t -f NAME,DEVICE,STATE connection show --active | head -n1 | cut -d: -f1)\n");
-            root_commands.push_str("  if [ -n \"$ACTIVE_CON\" ]; then\n");
-            root_commands.push_str("    nmcli connection modify \"$ACTIVE_CON\" ipv4.dns \"1.1.1.1 1.0.0.1\"\n");
-            root_commands.push_str("    nmcli connection modify \"$ACTIVE_CON\" ipv4.ignore-auto-dns yes\n");
-            root_commands.push_str("  fi\n");
-            root_commands.push_str("fi\n");
+            let (dns_commands, _) = dns::backend_commands(&dns_config, dns::resolve_backend(dns_config.transport));
+            root_commands.push_str(&dns_commands);
              if !needs_root_permission { needs_root_permission = true; }
         }
         {
             root_commands.push_str("echo \"STATUS:FINALIZING\"\n");
             let init = get_init_system();
+            let restart_nm = dns::network_manager_running();
             if init == "openrc" {
-                root_commands.push_str("rc-service NetworkManager restart\n");
+                if restart_nm { root_commands.push_str("rc-service NetworkManager restart\n"); }
                 root_commands.push_str("rc-update add dnscrypt-proxy default\n");
                 root_commands.push_str("rc-service dnscrypt-proxy start\n");
             }
             else if init == "runit" {
-                root_commands.push_str("sv restart NetworkManager || true\n");
+                if restart_nm { root_commands.push_str("sv restart NetworkManager || true\n"); }
                 root_commands.push_str("ln -sf /etc/sv/dnscrypt-proxy /var/service/\n");
                 root_commands.push_str("sleep 5\n");
                 root_commands.push_str("sv up dnscrypt-proxy || true\n");
             }
             else {
-                root_commands.push_str("systemctl restart NetworkManager\n");
+                if restart_nm { root_commands.push_str("systemctl restart NetworkManager\n"); }
                 root_commands.push_str("systemctl enable dnscrypt-proxy.service\n");
                 root_commands.push_str("systemctl start dnscrypt-proxy.service\n");
             }
@@ -2233,7 +4311,7 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
                         } else if l.contains("STATUS:CONFIGURING") {
                             let _ = sender.send(AppMsg::Status(t("DNS ayarları yapılıyor...")));
                         } else if l.contains("STATUS:SETTING_DNS") {
-                            let _ = sender.send(AppMsg::Status(t("Cloudflare DNS ayarlanıyor...")));
+                            let _ = sender.send(AppMsg::Status(t("DNS ayarlanıyor ({})...").replace("{}", &dns::load().describe())));
                         } else if l.contains("STATUS:FINALIZING") {
                             let _ = sender.send(AppMsg::Status(t("Ağ ayarları ve servisler başlatılıyor...")));
                         }
@@ -2246,9 +4324,17 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
             let status = child.wait();
             match status {
                 Ok(s) if s.success() => {
-                    let _ = sender.send(AppMsg::Status(t("NetworkManager Bekleniyor...")));
                     let _ = fs::remove_file(script_path);
-                    thread::sleep(Duration::from_secs(10));
+                    if configure_dnscrypt_later {
+                        let _ = sender.send(AppMsg::Status(t("DNS ayarları yapılıyor...")));
+                        if let Err(e) = dns::configure_dnscrypt(&dns_config) {
+                            log_to_file(&format!("Could not configure dnscrypt-proxy: {}", e));
+                        }
+                    }
+                    if dns::network_manager_running() {
+                        let _ = sender.send(AppMsg::Status(t("NetworkManager Bekleniyor...")));
+                        thread::sleep(Duration::from_secs(10));
+                    }
                 },
                 Ok(s) => {
                     let error_msg = if !last_error_line.is_empty() {
@@ -2269,71 +4355,82 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
         }
         if cancel_flag_thread.load(Ordering::Relaxed) { return; }
         if !zapret_full_path.exists() {
-            let _ = sender.send(AppMsg::Status(t("Zapret deposu indiriliyor...")));
-            let git_output = Command::new("git")
-                .args(["clone", "https://github.com/bol-van/zapret.git", zapret_path_str.as_str()])
-                .output();
-            match git_output {
-                Ok(output) => {
-                    if !output.stdout.is_empty() {
-                         let out = String::from_utf8_lossy(&output.stdout);
-                         println!("[GIT_OUT]: {}", out);
-                         log_to_file(&format!("[GIT_OUT]: {}", out));
-                    }
-                    if !output.stderr.is_empty() {
-                         let err = String::from_utf8_lossy(&output.stderr);
-                         println!("[GIT_ERR]: {}", err);
-                         log_to_file(&format!("[GIT_ERR]: {}", err));
-                    }
-                    if output.status.success() {
-                        if cancel_flag_thread.load(Ordering::Relaxed) { return; }
-                        let _ = sender.send(AppMsg::Status(t("Zapret derleniyor (make)...")));
-                        let mut make_cmd = Command::new("make");
-                        make_cmd.arg("-C").arg(&zapret_full_path);
-                        make_cmd.stdout(Stdio::piped());
-                        make_cmd.stderr(Stdio::piped());
-                        if let Ok(mut child) = make_cmd.spawn() {
-                            let _ = sender.send(AppMsg::PID(child.id()));
-                            let mut make_last_error = String::new();
-                            if let Some(stdout) = child.stdout.take() {
-                                let reader = BufReader::new(stdout);
-                                for line in reader.lines() {
-                                    if let Ok(l) = line { 
-                                        println!("[MAKE_OUT]: {}", l); 
-                                        log_to_file(&format!("[MAKE_OUT]: {}", l));
-                                        make_last_error = l;
-                                    }
-                                }
-                            }
-                            if let Some(stderr) = child.stderr.take() {
-                                let reader = BufReader::new(stderr);
-                                for line in reader.lines() {
-                                    if let Ok(l) = line {
-                                        println!("[MAKE_ERR]: {}", l);
-                                        log_to_file(&format!("[MAKE_ERR]: {}", l));
-                                        make_last_error = l;
-                                    }
-                                }
+            let fetched = match source::local_source() {
+                Some(local) => {
+                    let _ = sender.send(AppMsg::Status(t("Yerel zapret kaynağı açılıyor...")));
+                    log_to_file(&format!("Unpacking local zapret source {}", local.display()));
+                    integrity::verify_local(&local)
+                        .inspect(|v| log_to_file(&format!("Local zapret source verified: {:?}", v)))
+                        .and_then(|_| source::unpack(&local, &zapret_full_path))
+                        .map_err(io::Error::other)
+                }
+                None if source::prebuilt_mode() => {
+                    let _ = sender.send(AppMsg::Status(t("Zapret sürüm arşivi indiriliyor...")));
+                    download_zapret_release(&zapret_full_path)
+                }
+                None => {
+                    let _ = sender.send(AppMsg::Status(t("Zapret deposu indiriliyor...")));
+                    let remote = version::remote();
+                    resolve_zapret_tag(&remote).and_then(|tag| {
+                        log_to_file(&format!("Cloning zapret {} from {}", tag.as_deref().unwrap_or("HEAD"), remote));
+                        version::fetch_source(&remote, tag.as_deref(), &zapret_full_path).map_err(io::Error::other)
+                    })
+                }
+            };
+            match fetched {
+                Ok(()) if source::prebuilt_mode() => {
+                    if cancel_flag_thread.load(Ordering::Relaxed) { return; }
+                    let _ = sender.send(AppMsg::Status(t("Önceden derlenmiş ikili dosyalar hazırlanıyor...")));
+                    let result = source::prepare_binaries(&zapret_full_path).map_err(io::Error::other);
+                    if cancel_flag_thread.load(Ordering::Relaxed) { return; }
+                    let _ = sender.send(AppMsg::Done(result));
+                },
+                Ok(()) => {
+                if cancel_flag_thread.load(Ordering::Relaxed) { return; }
+                let _ = sender.send(AppMsg::Status(t("Zapret derleniyor (make)...")));
+                let mut make_cmd = Command::new("make");
+                make_cmd.arg("-C").arg(&zapret_full_path);
+                make_cmd.stdout(Stdio::piped());
+                make_cmd.stderr(Stdio::piped());
+                if let Ok(mut child) = make_cmd.spawn() {
+                    let _ = sender.send(AppMsg::PID(child.id()));
+                    let mut make_last_error = String::new();
+                    if let Some(stdout) = child.stdout.take() {
+                        let reader = BufReader::new(stdout);
+                        for line in reader.lines() {
+                            if let Ok(l) = line { 
+                                println!("[MAKE_OUT]: {}", l); 
+                                log_to_file(&format!("[MAKE_OUT]: {}", l));
+                                make_last_error = l;
                             }
-                            let make_result = child.wait();
-                            if cancel_flag_thread.load(Ordering::Relaxed) { return; }
-                            match make_result {
-                                Ok(m) if m.success() => {
-                                    let _ = sender.send(AppMsg::Done(Ok(())));
-                                },
-                                Ok(m) => {
-                                    let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::Other, t("Make hatası ({c}): {e}").replace("{c}", &m.code().unwrap_or(-1).to_string()).replace("{e}", &make_last_error)))));
-                                },
-                                Err(e) => {
-                                    let _ = sender.send(AppMsg::Done(Err(e)));
-                                }
+                        }
+                    }
+                    if let Some(stderr) = child.stderr.take() {
+                        let reader = BufReader::new(stderr);
+                        for line in reader.lines() {
+                            if let Ok(l) = line {
+                                println!("[MAKE_ERR]: {}", l);
+                                log_to_file(&format!("[MAKE_ERR]: {}", l));
+                                make_last_error = l;
                             }
-                        } else {
-                            let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::Other, t("Make komutu başlatılamadı. 'make' kurulu mu?")))));
                         }
-                    } else {
-                         let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::Other, t("Git clone hatası.")))));
                     }
+                    let make_result = child.wait();
+                    if cancel_flag_thread.load(Ordering::Relaxed) { return; }
+                    match make_result {
+                        Ok(m) if m.success() => {
+                            let _ = sender.send(AppMsg::Done(Ok(())));
+                        },
+                        Ok(m) => {
+                            let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::Other, t("Make hatası ({c}): {e}").replace("{c}", &m.code().unwrap_or(-1).to_string()).replace("{e}", &make_last_error)))));
+                        },
+                        Err(e) => {
+                            let _ = sender.send(AppMsg::Done(Err(e)));
+                        }
+                    }
+                } else {
+                    let _ = sender.send(AppMsg::Done(Err(io::Error::new(io::ErrorKind::Other, t("Make komutu başlatılamadı. 'make' kurulu mu?")))));
+                }
                 },
                 Err(e) => {
                     let _ = sender.send(AppMsg::Done(Err(e)));
@@ -2377,6 +4474,7 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
                         match result {
                             Ok(_) => {
                                 log_to_file("Installation process completed successfully.");
+                                notify::send("install", &t("Kurulum Tamamlandı"), &t("Kurulum bitti. Devam edebilirsiniz."), false, &[]);
                                 btn.set_label(&t("Devam"));
                                 btn.remove_css_class("destructive-action");
                                 btn.add_css_class("success");
@@ -2389,6 +4487,7 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
                                 btn.remove_css_class("destructive-action");
                                 btn.add_css_class("warning");
                                 lbl.set_label(&t("Hata: {}").replace("{}", &e.to_string()));
+                                confirm_pending_release(&btn);
                             }
                         }
                         glib::ControlFlow::Break
@@ -2400,6 +4499,57 @@ fn run_installation(btn: Button, pb: ProgressBar, lbl: Label, placeholder: Label
         }
     });
 }
+fn control_service(action: &str) {
+    if action != "start" {
+        EXPECTED_STOP.store(true, Ordering::Relaxed);
+    }
+    log_to_file(&format!("Service {} requested.", action));
+    let init = get_init_system();
+    let args: Vec<&str> = if init == "openrc" {
+        vec!["rc-service", "zapret", action]
+    } else if init == "runit" {
+        let sv_action = match action {
+            "start" => "up",
+            "stop" => "down",
+            other => other,
+        };
+        vec!["sv", sv_action, "zapret"]
+    } else {
+        vec!["systemctl", action, "zapret"]
+    };
+    let _ = Command::new("pkexec").args(args).spawn();
+}
+fn get_service_status() -> (bool, String) {
+    let init_sys = get_init_system();
+    let mut is_active = false;
+    let mut status_text = String::from("unknown");
+    if init_sys == "systemd" {
+        if let Ok(o) = Command::new("systemctl").arg("is-active").arg("zapret").output() {
+            status_text = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            if status_text == "active" { is_active = true; }
+        }
+    } else if init_sys == "openrc" {
+        if let Ok(o) = Command::new("rc-service").arg("zapret").arg("status").output() {
+            if o.status.success() { 
+                is_active = true; 
+                status_text = "active".to_string();
+            } else {
+                status_text = "stopped".to_string();
+            }
+        }
+    } else if init_sys == "runit" {
+        if let Ok(o) = Command::new("sv").arg("status").arg("zapret").output() {
+            let out = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            if out.starts_with("run:") { 
+                is_active = true; 
+                status_text = "active".to_string();
+            } else {
+                status_text = out;
+            }
+        }
+    }
+    (is_active, status_text)
+}
 fn get_init_system() -> String {
     if Path::new("/run/systemd/system").exists() {
         return "systemd".to_string();
@@ -2479,6 +4629,11 @@ fn get_distro_package_name(distro: &str, package: &str) -> String {
             "void" => p = "libcap-devel".to_string(),
             _ => p = "libcap-devel".to_string(),
         }
+    } else if package == "nft" {
+        match distro {
+            "gentoo" => p = "net-firewall/nftables".to_string(),
+            _ => p = "nftables".to_string(),
+        }
     } else if package == "dig" {
         match distro {
             "void" | "fedora" | "nobara" => p = "bind-utils".to_string(),
@@ -2487,6 +4642,8 @@ fn get_distro_package_name(distro: &str, package: &str) -> String {
             "gentoo" => p = "net-dns/bind-tools".to_string(),
             _ => p = "dnsutils".to_string(),
         }
+    } else if package == "unzip" && distro == "gentoo" {
+        p = "app-arch/unzip".to_string();
     }
     p
 }
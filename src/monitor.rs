@@ -0,0 +1,140 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use crate::{get_config_dir, settings, t};
+
+pub const DEFAULT_INTERVAL_MINUTES: u32 = 30;
+pub const DEFAULT_THRESHOLD: u32 = 70;
+const HISTORY_LIMIT: usize = 1000;
+
+pub struct HealthSample {
+    pub time: i64,
+    pub succeeded: usize,
+    pub total: usize,
+    pub failed: Vec<String>,
+}
+
+impl HealthSample {
+    pub fn rate(&self) -> u32 {
+        if self.total == 0 {
+            return 100;
+        }
+        (self.succeeded * 100 / self.total) as u32
+    }
+}
+
+pub fn is_enabled() -> bool {
+    settings::get_bool("monitor_enabled", false)
+}
+
+pub fn interval_minutes() -> u32 {
+    settings::get_u32("monitor_interval", DEFAULT_INTERVAL_MINUTES).max(1)
+}
+
+pub fn threshold() -> u32 {
+    settings::get_u32("monitor_threshold", DEFAULT_THRESHOLD).min(100)
+}
+
+pub fn get_history_path() -> PathBuf {
+    get_config_dir().join("health_history.txt")
+}
+
+pub fn load_history() -> Vec<HealthSample> {
+    let content = fs::read_to_string(get_history_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(4, '\t').collect();
+            if parts.len() < 3 {
+                return None;
+            }
+            Some(HealthSample {
+                time: parts[0].parse().ok()?,
+                succeeded: parts[1].parse().ok()?,
+                total: parts[2].parse().ok()?,
+                failed: parts
+                    .get(3)
+                    .map(|f| f.split(',').filter(|d| !d.is_empty()).map(|d| d.to_string()).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+pub fn append_history(sample: &HealthSample) -> io::Result<()> {
+    let history = load_history();
+    if history.len() >= HISTORY_LIMIT {
+        let mut content = String::new();
+        for old in &history[history.len() + 1 - HISTORY_LIMIT..] {
+            content.push_str(&format_sample(old));
+        }
+        fs::write(get_history_path(), content)?;
+    }
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(get_history_path())?;
+    file.write_all(format_sample(sample).as_bytes())
+}
+
+fn format_sample(sample: &HealthSample) -> String {
+    format!("{}\t{}\t{}\t{}\n", sample.time, sample.succeeded, sample.total, sample.failed.join(","))
+}
+
+pub fn average_rate(history: &[HealthSample], since: i64) -> Option<u32> {
+    let (succeeded, total) = history
+        .iter()
+        .filter(|s| s.time >= since)
+        .fold((0, 0), |(ok, all), s| (ok + s.succeeded, all + s.total));
+    (succeeded * 100).checked_div(total).map(|rate| rate as u32)
+}
+
+pub fn probe_domain(domain: &str) -> bool {
+    Command::new("curl")
+        .args(["-so", "/dev/null", "--max-time", "10", "-w", "%{http_code}"])
+        .arg(format!("https://{}/", domain))
+        .output()
+        .map(|o| {
+            let code = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            !code.is_empty() && code != "000"
+        })
+        .unwrap_or(false)
+}
+
+pub fn run_probe(domains: &[String], time: i64) -> HealthSample {
+    let handles: Vec<(String, thread::JoinHandle<bool>)> = domains
+        .iter()
+        .map(|d| {
+            let domain = d.clone();
+            (d.clone(), thread::spawn(move || probe_domain(&domain)))
+        })
+        .collect();
+    let mut failed = Vec::new();
+    for (domain, handle) in handles {
+        if !handle.join().unwrap_or(false) {
+            failed.push(domain);
+        }
+    }
+    HealthSample {
+        time,
+        succeeded: domains.len() - failed.len(),
+        total: domains.len(),
+        failed,
+    }
+}
+
+pub fn summary() -> String {
+    let history = load_history();
+    let Some(last) = history.last() else {
+        return t("Henüz kontrol yapılmadı.");
+    };
+    let day_ago = last.time - 24 * 60 * 60;
+    let mut text = t("Son kontrol: {s}/{n} alan adına erişildi (%{r})")
+        .replace("{s}", &last.succeeded.to_string())
+        .replace("{n}", &last.total.to_string())
+        .replace("{r}", &last.rate().to_string());
+    if let Some(rate) = average_rate(&history, day_ago) {
+        text.push('\n');
+        text.push_str(&t("Son 24 saat: %{}").replace("{}", &rate.to_string()));
+    }
+    text
+}
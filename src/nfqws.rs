@@ -0,0 +1,266 @@
+pub const HOSTLIST_DIR: &str = "/opt/zapret/ipset";
+
+pub struct NfqwsProfile {
+    pub filters: Vec<String>,
+    pub hostlist: Option<String>,
+    pub args: String,
+}
+
+const HTTP_ONLY_OPTIONS: &[&str] = &["--dpi-desync-fake-http", "--hostcase", "--hostspell", "--hostnospace", "--domcase", "--methodeol", "--methodspace", "--hostdot", "--hosttab", "--dpi-desync-split-http-req"];
+const TLS_ONLY_OPTIONS: &[&str] = &["--dpi-desync-fake-tls", "--dpi-desync-split-tls", "--dpi-desync-fake-tls-mod"];
+const QUIC_ONLY_OPTIONS: &[&str] = &["--dpi-desync-fake-quic", "--dpi-desync-fake-unknown-udp", "--dpi-desync-udplen-increment", "--dpi-desync-udplen-pattern"];
+
+fn has_option(strategy: &str, names: &[&str]) -> bool {
+    strategy.split_whitespace().any(|arg| {
+        let name = arg.split('=').next().unwrap_or(arg);
+        names.contains(&name)
+    })
+}
+
+pub fn strategy_filters(strategy: &str) -> Vec<String> {
+    if has_option(strategy, &["--filter-tcp", "--filter-udp", "--filter-l7"]) {
+        return Vec::new();
+    }
+    if has_option(strategy, QUIC_ONLY_OPTIONS) || strategy.contains("udplen") {
+        return vec!["--filter-udp=443".to_string()];
+    }
+    let http = has_option(strategy, HTTP_ONLY_OPTIONS);
+    let tls = has_option(strategy, TLS_ONLY_OPTIONS);
+    if http && !tls {
+        vec!["--filter-tcp=80".to_string()]
+    } else if tls && !http {
+        vec!["--filter-tcp=443".to_string()]
+    } else {
+        vec!["--filter-tcp=80,443".to_string()]
+    }
+}
+
+pub fn build_profile(strategy: &str, hostlist: Option<&str>) -> NfqwsProfile {
+    let args = strategy.split_whitespace().collect::<Vec<_>>().join(" ");
+    let hostlist = if has_option(&args, &["--hostlist", "--hostlist-domains", "--hostlist-auto"]) {
+        None
+    } else {
+        hostlist.map(|h| h.to_string())
+    };
+    NfqwsProfile {
+        filters: strategy_filters(&args),
+        hostlist,
+        args,
+    }
+}
+
+pub fn profile_line(profile: &NfqwsProfile) -> String {
+    let mut parts: Vec<String> = profile.filters.clone();
+    parts.push(profile.args.clone());
+    if let Some(hostlist) = &profile.hostlist {
+        parts.push(format!("--hostlist={}", hostlist));
+    }
+    parts.join(" ")
+}
+
+fn filter_ports(profile: &NfqwsProfile) -> Option<Vec<(String, String)>> {
+    if profile.filters.is_empty() || has_option(&profile.args, &["--hostlist", "--hostlist-domains", "--hostlist-auto", "--ipset"]) {
+        return None;
+    }
+    let mut ports = Vec::new();
+    for filter in &profile.filters {
+        let (name, list) = filter.split_once('=')?;
+        let proto = name.strip_prefix("--filter-")?;
+        ports.extend(list.split(',').map(|p| (proto.to_string(), p.to_string())));
+    }
+    Some(ports)
+}
+
+// nfqws uses the first matching profile, so one fully covered by earlier profiles never runs.
+fn shadowed_profiles(profiles: &[NfqwsProfile]) -> Vec<usize> {
+    let mut shadowed = Vec::new();
+    for (i, profile) in profiles.iter().enumerate() {
+        let Some(ports) = filter_ports(profile) else { continue };
+        let earlier: Vec<(String, String)> = profiles[..i]
+            .iter()
+            .filter(|p| p.hostlist.is_none() || p.hostlist == profile.hostlist)
+            .filter_map(filter_ports)
+            .flatten()
+            .collect();
+        if ports.iter().all(|port| earlier.contains(port)) {
+            shadowed.push(i);
+        }
+    }
+    shadowed
+}
+
+pub fn compose_nfqws_opt(profiles: &[NfqwsProfile]) -> String {
+    let lines: Vec<String> = profiles.iter().map(profile_line).collect();
+    format!("\n{}\n", lines.join(" --new\n"))
+}
+
+pub struct ApplyPlan {
+    pub profiles: Vec<NfqwsProfile>,
+    pub hostlists: Vec<(String, Vec<String>)>,
+    pub idle: Vec<String>,
+}
+
+pub fn hostlist_path(index: usize) -> String {
+    if index == 0 {
+        format!("{}/zapret-gtk-hosts.txt", HOSTLIST_DIR)
+    } else {
+        format!("{}/zapret-gtk-hosts-{}.txt", HOSTLIST_DIR, index)
+    }
+}
+
+pub fn plan_profiles(selected: &[String], saved_domains: &[String], mapping: &[(String, String)]) -> ApplyPlan {
+    let mut profiles = Vec::new();
+    let mut hostlists = Vec::new();
+    let mut mapped_domains = Vec::new();
+    let mut default_strategies = Vec::new();
+    for strategy in selected {
+        let domains: Vec<String> = mapping
+            .iter()
+            .filter(|(d, s)| s == strategy && !mapped_domains.contains(d))
+            .map(|(d, _)| d.clone())
+            .collect();
+        if domains.is_empty() {
+            default_strategies.push(strategy.clone());
+            continue;
+        }
+        let path = hostlist_path(hostlists.len() + 1);
+        profiles.push(build_profile(strategy, Some(&path)));
+        mapped_domains.extend(domains.iter().cloned());
+        hostlists.push((path, domains));
+    }
+    let unmapped: Vec<String> = saved_domains
+        .iter()
+        .filter(|d| !mapped_domains.contains(d))
+        .cloned()
+        .collect();
+    if default_strategies.is_empty() && !unmapped.is_empty() {
+        if let Some(first) = selected.first() {
+            default_strategies.push(first.clone());
+        }
+    }
+    let default_hostlist = if unmapped.is_empty() {
+        None
+    } else {
+        let path = hostlist_path(0);
+        hostlists.push((path.clone(), unmapped));
+        Some(path)
+    };
+    let mut defaults: Vec<NfqwsProfile> = Vec::new();
+    let mut idle = Vec::new();
+    for strategy in &default_strategies {
+        defaults.push(build_profile(strategy, default_hostlist.as_deref()));
+        if shadowed_profiles(&defaults).contains(&(defaults.len() - 1)) {
+            defaults.pop();
+            idle.push(strategy.clone());
+        }
+    }
+    profiles.extend(defaults);
+    ApplyPlan { profiles, hostlists, idle }
+}
+
+pub const DESYNC_PHASE0_MODES: &[&str] = &["synack", "syndata"];
+pub const DESYNC_PHASE1_MODES: &[&str] = &["fake", "fakeknown", "rst", "rstack", "hopbyhop", "destopt", "ipfrag1"];
+pub const DESYNC_PHASE2_MODES: &[&str] = &["multisplit", "multidisorder", "fakedsplit", "fakeddisorder", "hostfakesplit", "split", "split2", "disorder", "disorder2", "ipfrag2", "udplen", "tamper"];
+pub const FOOLING_VALUES: &[&str] = &["none", "md5sig", "badsum", "badseq", "datanoack", "ts", "hopbyhop", "hopbyhop2"];
+pub const SPLIT_POS_MARKERS: &[&str] = &["method", "host", "endhost", "sld", "midsld", "endsld", "sniext"];
+
+pub fn parse_strategy_args(strategy: &str) -> Vec<(String, Option<String>)> {
+    let tokens: Vec<&str> = strategy.split_whitespace().collect();
+    let mut args = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        if let Some((name, value)) = token.split_once('=') {
+            args.push((name.to_string(), Some(value.to_string())));
+        } else if token.starts_with("--") && i + 1 < tokens.len() && !tokens[i + 1].starts_with("--") {
+            args.push((token.to_string(), Some(tokens[i + 1].to_string())));
+            i += 1;
+        } else {
+            args.push((token.to_string(), None));
+        }
+        i += 1;
+    }
+    args
+}
+
+pub fn is_valid_split_pos(value: &str) -> bool {
+    !value.is_empty() && value.split(',').all(|pos| {
+        let pos = pos.trim();
+        let base_end = pos.find(['+', '-']).filter(|&i| i > 0).unwrap_or(pos.len());
+        let (base, offset) = pos.split_at(base_end);
+        let base_ok = SPLIT_POS_MARKERS.contains(&base) || base.trim_start_matches('-').parse::<u32>().is_ok();
+        let offset_ok = offset.is_empty() || offset[1..].parse::<u32>().is_ok();
+        base_ok && offset_ok
+    })
+}
+
+#[derive(Clone, Default)]
+pub struct StrategyFields {
+    pub phase0: Option<String>,
+    pub phase1: Option<String>,
+    pub phase2: Option<String>,
+    pub other_modes: Vec<String>,
+    pub ttl: Option<u32>,
+    pub fooling: Vec<String>,
+    pub split_pos: Option<String>,
+    pub repeats: Option<u32>,
+    pub extra: Vec<String>,
+}
+
+impl StrategyFields {
+    pub fn parse(strategy: &str) -> StrategyFields {
+        let mut fields = StrategyFields::default();
+        for (name, value) in parse_strategy_args(strategy) {
+            match (name.as_str(), value) {
+                ("--dpi-desync", Some(v)) => {
+                    for mode in v.split(',') {
+                        if DESYNC_PHASE0_MODES.contains(&mode) {
+                            fields.phase0 = Some(mode.to_string());
+                        } else if DESYNC_PHASE1_MODES.contains(&mode) {
+                            fields.phase1 = Some(mode.to_string());
+                        } else if DESYNC_PHASE2_MODES.contains(&mode) {
+                            fields.phase2 = Some(mode.to_string());
+                        } else if !mode.is_empty() {
+                            fields.other_modes.push(mode.to_string());
+                        }
+                    }
+                },
+                ("--dpi-desync-ttl", Some(v)) if v.parse::<u32>().is_ok() => fields.ttl = v.parse().ok(),
+                ("--dpi-desync-repeats", Some(v)) if v.parse::<u32>().is_ok() => fields.repeats = v.parse().ok(),
+                ("--dpi-desync-fooling", Some(v)) => {
+                    fields.fooling = v.split(',').filter(|f| !f.is_empty()).map(|f| f.to_string()).collect();
+                },
+                ("--dpi-desync-split-pos", Some(v)) => fields.split_pos = Some(v),
+                (_, Some(v)) => fields.extra.push(format!("{}={}", name, v)),
+                (_, None) => fields.extra.push(name),
+            }
+        }
+        fields
+    }
+
+    pub fn to_strategy(&self) -> String {
+        let mut parts = Vec::new();
+        let modes: Vec<String> = [&self.phase0, &self.phase1, &self.phase2]
+            .iter()
+            .filter_map(|m| m.as_ref().cloned())
+            .chain(self.other_modes.iter().cloned())
+            .collect();
+        if !modes.is_empty() {
+            parts.push(format!("--dpi-desync={}", modes.join(",")));
+        }
+        if let Some(ttl) = self.ttl {
+            parts.push(format!("--dpi-desync-ttl={}", ttl));
+        }
+        if !self.fooling.is_empty() {
+            parts.push(format!("--dpi-desync-fooling={}", self.fooling.join(",")));
+        }
+        if let Some(pos) = &self.split_pos {
+            parts.push(format!("--dpi-desync-split-pos={}", pos));
+        }
+        if let Some(repeats) = self.repeats {
+            parts.push(format!("--dpi-desync-repeats={}", repeats));
+        }
+        parts.extend(self.extra.iter().cloned());
+        parts.join(" ")
+    }
+}
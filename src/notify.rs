@@ -0,0 +1,23 @@
+use gtk4 as gtk;
+use gtk::gio;
+use gtk::prelude::*;
+
+pub const ACTION_RESTART: &str = "app.restart";
+pub const ACTION_OPEN_LOGS: &str = "app.open-logs";
+pub const ACTION_SHOW_WINDOW: &str = "app.show-window";
+
+pub fn send(id: &str, title: &str, body: &str, urgent: bool, buttons: &[(String, &str)]) {
+    let Some(app) = gio::Application::default() else {
+        return;
+    };
+    let notification = gio::Notification::new(title);
+    notification.set_body(Some(body));
+    notification.set_default_action(ACTION_SHOW_WINDOW);
+    if urgent {
+        notification.set_priority(gio::NotificationPriority::Urgent);
+    }
+    for (label, action) in buttons {
+        notification.add_button(label, action);
+    }
+    app.send_notification(Some(id), &notification);
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::{domains, get_config_dir};
+
+const BUILTIN_PRESETS: &[(&str, &[&str])] = &[
+    ("YouTube", &["youtube.com", "youtu.be", "yt.be", "youtube-nocookie.com", "googlevideo.com", "ytimg.com", "ggpht.com", "youtubei.googleapis.com"]),
+    ("Discord", &["discord.com", "discord.gg", "discord.co", "discord.media", "discord.gift", "discord.new", "discord.dev", "discordapp.com", "discordapp.net", "discordcdn.com", "discordstatus.com", "dis.gd"]),
+    ("Twitter / X", &["x.com", "twitter.com", "t.co", "twimg.com", "ads-twitter.com", "tweetdeck.com", "pscp.tv", "periscope.tv"]),
+    ("Instagram", &["instagram.com", "cdninstagram.com", "instagr.am", "ig.me", "fbcdn.net"]),
+    ("Facebook", &["facebook.com", "fb.com", "fb.me", "facebook.net", "fbcdn.net", "fbsbx.com", "messenger.com", "m.me"]),
+    ("WhatsApp", &["whatsapp.com", "whatsapp.net", "wa.me"]),
+    ("Telegram", &["telegram.org", "telegram.me", "t.me", "telesco.pe", "telegra.ph", "tdesktop.com"]),
+    ("Twitch", &["twitch.tv", "ttvnw.net", "jtvnw.net", "twitchcdn.net", "twitchsvc.net", "ext-twitch.tv"]),
+    ("Roblox", &["roblox.com", "rbxcdn.com", "rbx.com", "robloxlabs.com"]),
+    ("Reddit", &["reddit.com", "redd.it", "redditmedia.com", "redditstatic.com"]),
+    ("Wikipedia", &["wikipedia.org", "wikimedia.org", "wikidata.org", "wiktionary.org"]),
+];
+
+pub struct Preset {
+    pub name: String,
+    pub domains: Vec<String>,
+}
+
+pub fn get_presets_dir() -> PathBuf {
+    get_config_dir().join("presets")
+}
+
+pub fn load_presets() -> Vec<Preset> {
+    let mut presets: Vec<Preset> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, list)| Preset {
+            name: name.to_string(),
+            domains: list.iter().map(|d| d.to_string()).collect(),
+        })
+        .collect();
+    let mut user_files: Vec<PathBuf> = match fs::read_dir(get_presets_dir()) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().map(|ext| ext == "txt").unwrap_or(false))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    user_files.sort();
+    for path in user_files {
+        if let Some(preset) = parse_preset_file(&path) {
+            if let Some(existing) = presets.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&preset.name)) {
+                *existing = preset;
+            } else {
+                presets.push(preset);
+            }
+        }
+    }
+    presets
+}
+
+fn parse_preset_file(path: &PathBuf) -> Option<Preset> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut name = path.file_stem()?.to_string_lossy().to_string();
+    let mut list = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(value) = comment.trim().strip_prefix("name:") {
+                name = value.trim().to_string();
+            }
+            continue;
+        }
+        for part in domains::split_domain_input(line) {
+            if let Ok(domain) = domains::normalize_domain(&part) {
+                if !list.contains(&domain) {
+                    list.push(domain);
+                }
+            }
+        }
+    }
+    if list.is_empty() {
+        return None;
+    }
+    Some(Preset { name, domains: list })
+}
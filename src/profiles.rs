@@ -0,0 +1,84 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use crate::{get_config_dir, settings};
+
+pub struct Profile {
+    pub name: String,
+    pub strategies: Vec<String>,
+}
+
+pub fn get_profiles_dir() -> PathBuf {
+    get_config_dir().join("profiles")
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    let file_name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    get_profiles_dir().join(format!("{}.txt", file_name))
+}
+
+pub fn load_profiles() -> Vec<Profile> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(get_profiles_dir()) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().map(|ext| ext == "txt").unwrap_or(false))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+        .iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            let mut name = path.file_stem()?.to_string_lossy().to_string();
+            let mut strategies = Vec::new();
+            for line in content.lines() {
+                let line = line.trim();
+                if let Some(comment) = line.strip_prefix('#') {
+                    if let Some(value) = comment.trim().strip_prefix("name:") {
+                        name = value.trim().to_string();
+                    }
+                } else if !line.is_empty() {
+                    strategies.push(line.to_string());
+                }
+            }
+            Some(Profile { name, strategies })
+        })
+        .collect()
+}
+
+pub fn find_profile(name: &str) -> Option<Profile> {
+    load_profiles().into_iter().find(|p| p.name == name)
+}
+
+pub fn profile_names() -> Vec<String> {
+    load_profiles().into_iter().map(|p| p.name).collect()
+}
+
+pub fn save_profile(name: &str, strategies: &[String]) -> io::Result<()> {
+    fs::create_dir_all(get_profiles_dir())?;
+    let mut content = format!("# name: {}\n", name);
+    for strategy in strategies {
+        content.push_str(strategy);
+        content.push('\n');
+    }
+    fs::write(profile_path(name), content)
+}
+
+pub fn delete_profile(name: &str) -> io::Result<()> {
+    if settings::get("active_profile").as_deref() == Some(name) {
+        settings::set("active_profile", "")?;
+    }
+    fs::remove_file(profile_path(name))
+}
+
+pub fn active_profile() -> Option<String> {
+    settings::get("active_profile").filter(|name| !name.is_empty())
+}
+
+pub fn set_active_profile(name: &str) -> io::Result<()> {
+    settings::set("active_profile", name)
+}
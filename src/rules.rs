@@ -0,0 +1,249 @@
+use std::fs;
+use std::io;
+use std::process::Command;
+use crate::benchmark::shell_quote;
+use crate::t;
+
+pub const DEFAULT_QNUM: u32 = 200;
+
+#[derive(Clone, Debug)]
+pub struct QueueRule {
+    pub family: String,
+    pub table: String,
+    pub chain: String,
+    pub queue: String,
+    pub ports: String,
+    pub sets: Vec<String>,
+    pub packets: Option<u64>,
+    pub bytes: Option<u64>,
+    pub spec: String,
+    pub handle: Option<u64>,
+}
+
+impl QueueRule {
+    pub fn is_zapret(&self, qnum: u32) -> bool {
+        if self.family == "nft" {
+            self.table.ends_with(" zapret")
+        } else {
+            self.queue.split(':').next() == Some(qnum.to_string().as_str())
+        }
+    }
+
+    pub fn location(&self) -> String {
+        format!("{} {}/{}", self.family, self.table, self.chain)
+    }
+
+    pub fn delete_and_restore_commands(&self) -> Option<(String, String)> {
+        if self.family == "nft" {
+            let handle = self.handle?;
+            if [&self.table, &self.chain].iter().any(|name| name.is_empty() || name.contains(['{', '#'])) {
+                return None;
+            }
+            return Some((
+                format!("nft delete rule {} {} handle {}", self.table, self.chain, handle),
+                format!("nft add rule {} {} {}", self.table, self.chain, shell_quote(&self.spec)),
+            ));
+        }
+        let rest = self.spec.strip_prefix("-A ")?;
+        Some((
+            format!("{} -t {} -D {}", self.family, self.table, rest),
+            format!("{} -t {} -A {}", self.family, self.table, rest),
+        ))
+    }
+}
+
+pub fn configured_qnum() -> u32 {
+    fs::read_to_string("/opt/zapret/config")
+        .ok()
+        .and_then(|content| {
+            content
+                .lines()
+                .filter_map(|l| l.trim().strip_prefix("QNUM="))
+                .next_back()
+                .and_then(|v| v.trim_matches(|c| c == '"' || c == '\'').parse().ok())
+        })
+        .unwrap_or(DEFAULT_QNUM)
+}
+
+fn option_value(tokens: &[&str], names: &[&str]) -> Option<String> {
+    tokens
+        .iter()
+        .position(|tok| names.contains(tok))
+        .and_then(|i| tokens.get(i + 1))
+        .map(|v| v.to_string())
+}
+
+fn parse_iptables_save(family: &str, output: &str) -> Vec<QueueRule> {
+    let mut rules = Vec::new();
+    let mut table = String::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('*') {
+            table = name.to_string();
+            continue;
+        }
+        let (counters, spec) = match line.strip_prefix('[').and_then(|l| l.split_once("] ")) {
+            Some((counters, spec)) => (Some(counters), spec),
+            None => (None, line),
+        };
+        if !spec.starts_with("-A ") || !spec.contains("-j NFQUEUE") {
+            continue;
+        }
+        let tokens: Vec<&str> = spec.split_whitespace().collect();
+        let (packets, bytes) = counters
+            .and_then(|c| c.split_once(':'))
+            .map(|(p, b)| (p.parse().ok(), b.parse().ok()))
+            .unwrap_or((None, None));
+        let mut sets = Vec::new();
+        for (i, tok) in tokens.iter().enumerate() {
+            if *tok == "--match-set" {
+                if let Some(name) = tokens.get(i + 1) {
+                    sets.push(name.to_string());
+                }
+            }
+        }
+        let proto = option_value(&tokens, &["-p"]).unwrap_or_default();
+        let ports = option_value(&tokens, &["--dports", "--dport"]).unwrap_or_default();
+        rules.push(QueueRule {
+            family: family.to_string(),
+            table: table.clone(),
+            chain: tokens.get(1).unwrap_or(&"").to_string(),
+            queue: option_value(&tokens, &["--queue-num", "--queue-balance"]).unwrap_or_else(|| "0".to_string()),
+            ports: format!("{} {}", proto, ports).trim().to_string(),
+            sets,
+            packets,
+            bytes,
+            spec: spec.to_string(),
+            handle: None,
+        });
+    }
+    rules
+}
+
+// iptables-nft tables also show up in `nft list ruleset`; iptables-save already covers them.
+fn is_xtables_table(table: &str) -> bool {
+    match table.split_once(' ') {
+        Some(("ip" | "ip6", name)) => matches!(name, "filter" | "mangle" | "nat" | "raw" | "security"),
+        _ => false,
+    }
+}
+
+// `table inet zapret { # handle 12` -> `inet zapret`
+fn block_name(header: &str) -> String {
+    header.split('{').next().unwrap_or(header).trim().to_string()
+}
+
+fn parse_nft_ruleset(output: &str) -> Vec<QueueRule> {
+    let mut rules = Vec::new();
+    let mut table = String::new();
+    let mut chain = String::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("table ") {
+            table = block_name(rest);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("chain ") {
+            chain = block_name(rest);
+            continue;
+        }
+        let (body, handle) = match line.rsplit_once(" # handle ") {
+            Some((body, handle)) => (body.trim(), handle.trim().parse().ok()),
+            None => (line, None),
+        };
+        if body.ends_with('{') || is_xtables_table(&table) {
+            continue;
+        }
+        let tokens: Vec<&str> = body.split_whitespace().collect();
+        let Some(queue_idx) = tokens.iter().position(|tok| *tok == "queue") else {
+            continue;
+        };
+        let queue = option_value(&tokens[queue_idx..], &["to", "num"]).unwrap_or_else(|| "0".to_string());
+        let port_idx = tokens.iter().position(|tok| *tok == "dport");
+        let ports = match port_idx {
+            Some(i) => {
+                let proto = if i > 0 { tokens[i - 1] } else { "" };
+                let mut value = String::new();
+                for tok in &tokens[i + 1..] {
+                    value.push_str(tok);
+                    if !value.starts_with('{') || tok.ends_with('}') {
+                        break;
+                    }
+                }
+                format!("{} {}", proto, value)
+            }
+            None => String::new(),
+        };
+        let counter = |name: &str| option_value(&tokens, &[name]).and_then(|v| v.parse().ok());
+        rules.push(QueueRule {
+            family: "nft".to_string(),
+            table: table.clone(),
+            chain: chain.clone(),
+            queue,
+            ports,
+            sets: tokens.iter().filter_map(|tok| tok.strip_prefix('@')).map(|s| s.to_string()).collect(),
+            packets: counter("packets"),
+            bytes: counter("bytes"),
+            spec: body.to_string(),
+            handle,
+        });
+    }
+    rules
+}
+
+fn split_sections(output: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("### ") {
+            sections.push((name.trim().to_string(), String::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    sections
+}
+
+pub fn inspect() -> io::Result<Vec<QueueRule>> {
+    let script = "echo '### iptables'; iptables-save -c 2>/dev/null; \
+                  echo '### ip6tables'; ip6tables-save -c 2>/dev/null; \
+                  echo '### nft'; nft -a list ruleset 2>/dev/null; true";
+    let output = Command::new("pkexec").arg("sh").arg("-c").arg(script).output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, t("Kurallar okunamadı (yetki verilmedi).")));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut rules = Vec::new();
+    for (name, body) in split_sections(&text) {
+        match name.as_str() {
+            "nft" => rules.extend(parse_nft_ruleset(&body)),
+            family => rules.extend(parse_iptables_save(family, &body)),
+        }
+    }
+    Ok(rules)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+pub fn find_problems(rules: &[QueueRule], service_active: bool, qnum: u32) -> Vec<String> {
+    let mut problems = Vec::new();
+    let zapret_rules: Vec<&QueueRule> = rules.iter().filter(|r| r.is_zapret(qnum)).collect();
+    if service_active && zapret_rules.is_empty() {
+        problems.push(t("Servis aktif görünüyor ancak zapret'in NFQUEUE kuralları (kuyruk {}) yüklü değil. Trafik nfqws'e ulaşmıyor.").replace("{}", &qnum.to_string()));
+    }
+    if service_active && !zapret_rules.is_empty() && zapret_rules.iter().all(|r| r.family == "ip6tables") {
+        problems.push(t("Yalnızca IPv6 kuralları bulundu; IPv4 trafiği işlenmiyor."));
+    }
+    if !service_active && !zapret_rules.is_empty() {
+        problems.push(t("Servis durmuş ancak kuyruk {} kuralları hâlâ yüklü. Bu kurallar bağlantıları kesebilir.").replace("{}", &qnum.to_string()));
+    }
+    problems
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use crate::get_config_dir;
+
+pub fn get_settings_path() -> PathBuf {
+    get_config_dir().join("settings.conf")
+}
+
+fn load_all() -> Vec<(String, String)> {
+    let content = fs::read_to_string(get_settings_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn get(key: &str) -> Option<String> {
+    load_all().into_iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+pub fn get_bool(key: &str, default: bool) -> bool {
+    match get(key).as_deref() {
+        Some("true") => true,
+        Some("false") => false,
+        _ => default,
+    }
+}
+
+pub fn get_u32(key: &str, default: u32) -> u32 {
+    get(key).and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+pub fn set(key: &str, value: &str) -> io::Result<()> {
+    let mut all = load_all();
+    match all.iter_mut().find(|(k, _)| k == key) {
+        Some(entry) => entry.1 = value.to_string(),
+        None => all.push((key.to_string(), value.to_string())),
+    }
+    let mut content = String::new();
+    for (k, v) in all {
+        content.push_str(&format!("{}={}\n", k, v));
+    }
+    fs::write(get_settings_path(), content)
+}
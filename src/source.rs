@@ -0,0 +1,204 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::{settings, t};
+
+pub const TAG_MARKER: &str = ".zapret-gtk-tag";
+
+const REQUIRED_FILES: [&str; 2] = ["install_easy.sh", "blockcheck.sh"];
+const BUILD_FILES: [&str; 2] = ["Makefile", "install_bin.sh"];
+pub const BUILD_LIBS: [&str; 4] = ["zlib", "libnetfilter_queue", "libmnl", "libcap"];
+
+pub fn local_source() -> Option<PathBuf> {
+    settings::get("zapret_local_source")
+        .filter(|v| !v.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+pub fn set_local_source(path: Option<&Path>) -> io::Result<()> {
+    settings::set("zapret_local_source", &path.map(|p| p.to_string_lossy().to_string()).unwrap_or_default())
+}
+
+pub fn describe() -> String {
+    match local_source() {
+        Some(path) if path.is_dir() => t("Kaynak: yerel klasör {}").replace("{}", &path.display().to_string()),
+        Some(path) => t("Kaynak: yerel arşiv {}").replace("{}", &path.display().to_string()),
+        None => t("Kaynak: {}").replace("{}", &crate::version::remote()),
+    }
+}
+
+pub fn is_zip(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip"))
+}
+
+pub fn tag_from_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let stem = [".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(&name);
+    let tag = stem.rsplit('-').next()?;
+    crate::version::parse_version(tag).map(|_| tag.to_string())
+}
+
+fn list_archive(path: &Path) -> Result<Vec<String>, String> {
+    let output = if is_zip(path) {
+        Command::new("unzip").arg("-Z1").arg(path).output()
+    } else {
+        Command::new("tar").arg("-tf").arg(path).output()
+    }
+    .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(t("Arşiv okunamadı: {}").replace("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(|l| l.to_string()).collect())
+}
+
+fn common_root(entries: &[String]) -> Option<String> {
+    let first = entries.first()?.trim_start_matches("./").split('/').next()?.to_string();
+    entries
+        .iter()
+        .all(|e| e.trim_start_matches("./").split('/').next() == Some(first.as_str()))
+        .then_some(first)
+}
+
+pub fn verify(path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        let mut missing: Vec<&str> = REQUIRED_FILES.iter().copied().filter(|f| !path.join(f).exists()).collect();
+        if !BUILD_FILES.iter().any(|f| path.join(f).exists()) {
+            missing.push(BUILD_FILES[0]);
+        }
+        if !missing.is_empty() {
+            return Err(t("Klasör bir zapret kaynağı değil; eksik dosyalar: {}").replace("{}", &missing.join(", ")));
+        }
+        return Ok(());
+    }
+    if !path.is_file() {
+        return Err(t("Kaynak bulunamadı: {}").replace("{}", &path.display().to_string()));
+    }
+    let entries = list_archive(path)?;
+    if let Some(bad) = entries.iter().find(|e| e.starts_with('/') || e.split('/').any(|part| part == "..")) {
+        return Err(t("Arşivde güvensiz yol var: {}").replace("{}", bad));
+    }
+    let prefix = common_root(&entries).map(|root| format!("{}/", root)).unwrap_or_default();
+    let contains = |f: &str| entries.iter().any(|e| e.trim_start_matches("./") == format!("{}{}", prefix, f));
+    let mut missing: Vec<&str> = REQUIRED_FILES.iter().copied().filter(|f| !contains(f)).collect();
+    if !BUILD_FILES.iter().any(|f| contains(f)) {
+        missing.push(BUILD_FILES[0]);
+    }
+    if !missing.is_empty() {
+        return Err(t("Arşiv bir zapret sürümü değil; eksik dosyalar: {}").replace("{}", &missing.join(", ")));
+    }
+    Ok(())
+}
+
+pub fn unpack(source: &Path, dest: &Path) -> Result<(), String> {
+    verify(source)?;
+    if dest.exists() {
+        fs::remove_dir_all(dest).map_err(|e| t("Eski kaynak klasörü silinemedi: {}").replace("{}", &e.to_string()))?;
+    }
+    let output = if source.is_dir() {
+        fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+        Command::new("cp").arg("-a").arg(source.join(".")).arg(dest).output()
+    } else if is_zip(source) {
+        let staging = dest.with_extension("unpack");
+        let _ = fs::remove_dir_all(&staging);
+        let output = Command::new("unzip").arg("-q").arg(source).arg("-d").arg(&staging).output();
+        if output.as_ref().is_ok_and(|o| o.status.success()) {
+            let entries = list_archive(source)?;
+            let root = common_root(&entries).map(|r| staging.join(r)).unwrap_or_else(|| staging.clone());
+            fs::rename(&root, dest).map_err(|e| e.to_string())?;
+            let _ = fs::remove_dir_all(&staging);
+        }
+        output
+    } else {
+        fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+        let strip = if common_root(&list_archive(source)?).is_some() { "1" } else { "0" };
+        Command::new("tar")
+            .arg("-xf")
+            .arg(source)
+            .arg("-C")
+            .arg(dest)
+            .arg(format!("--strip-components={}", strip))
+            .output()
+    }
+    .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(t("Arşiv açılamadı: {}").replace("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    if let Some(tag) = tag_from_name(source) {
+        let _ = fs::write(dest.join(TAG_MARKER), tag);
+    }
+    Ok(())
+}
+
+pub fn prebuilt_mode() -> bool {
+    settings::get_bool("zapret_prebuilt", false)
+}
+
+pub fn set_prebuilt_mode(enabled: bool) -> io::Result<()> {
+    settings::set("zapret_prebuilt", if enabled { "true" } else { "false" })
+}
+
+pub fn release_archive_url(remote: &str, tag: &str) -> Option<String> {
+    let repo = remote.trim_end_matches('/').trim_end_matches(".git");
+    repo.starts_with("https://github.com/")
+        .then(|| format!("{}/releases/download/{1}/zapret-{1}.tar.gz", repo, tag))
+}
+
+pub fn download_release(remote: &str, tag: &str, dest: &Path) -> Result<(), String> {
+    let Some(url) = release_archive_url(remote, tag) else {
+        return Err(t("Önceden derlenmiş sürümler yalnızca GitHub deposundan veya yerel bir sürüm arşivinden alınabilir."));
+    };
+    let archive = std::env::temp_dir().join(format!("zapret-{}.tar.gz", tag));
+    let output = crate::version::network_command("curl")
+        .args(["-fsSL", "--max-time", "300", "-o"])
+        .arg(&archive)
+        .arg(&url)
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(t("Sürüm arşivi indirilemedi: {}").replace("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let result = crate::integrity::verify_archive(&archive)
+        .inspect(|v| crate::log_to_file(&format!("Release archive verified: {:?}", v)))
+        .and_then(|_| unpack(&archive, dest));
+    let _ = fs::remove_file(&archive);
+    result
+}
+
+fn command_exists(name: &str) -> bool {
+    Command::new("which").arg(name).output().map(|o| o.status.success()).unwrap_or(false)
+}
+
+pub fn prepare_binaries(dir: &Path) -> Result<(), String> {
+    let install_bin = dir.join("install_bin.sh");
+    let failure = if install_bin.exists() {
+        match Command::new("sh").arg(&install_bin).current_dir(dir).output() {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+                text.lines().last().unwrap_or("").to_string()
+            }
+            Err(e) => e.to_string(),
+        }
+    } else {
+        t("Kaynakta install_bin.sh yok")
+    };
+    crate::log_to_file(&format!("Prebuilt binaries unusable ({}), falling back to make", failure));
+    let distro = crate::get_distro_id();
+    let missing_libs: Vec<&str> = BUILD_LIBS
+        .iter()
+        .copied()
+        .filter(|lib| !crate::is_package_installed(&distro, &crate::get_distro_package_name(&distro, lib)))
+        .collect();
+    if !missing_libs.is_empty() {
+        crate::log_to_file(&format!("Cannot compile, missing libraries: {}", missing_libs.join(", ")));
+    }
+    if !dir.join("Makefile").exists() || !command_exists("make") || !command_exists("cc") || !missing_libs.is_empty() {
+        return Err(t("Önceden derlenmiş ikili dosyalar bu sistemde kullanılamıyor ({}) ve derleme araçları kurulu değil. Derleyerek kurmayı seçin.")
+            .replace("{}", &failure));
+    }
+    crate::version::build(dir)
+}
@@ -0,0 +1,411 @@
+use gtk4 as gtk;
+use gtk::{gio, glib};
+use gtk::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::{log_to_file, t};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+const ICON_NAME: &str = "zapret-gtk";
+
+const ITEM_XML: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="AttentionIconName" type="s" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="ContextMenu"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Activate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="SecondaryActivate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Scroll"><arg name="delta" type="i" direction="in"/><arg name="orientation" type="s" direction="in"/></method>
+    <signal name="NewTitle"/>
+    <signal name="NewIcon"/>
+    <signal name="NewAttentionIcon"/>
+    <signal name="NewToolTip"/>
+    <signal name="NewStatus"><arg name="status" type="s"/></signal>
+  </interface>
+</node>
+"#;
+
+const MENU_XML: &str = r#"
+<node>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="EventGroup">
+      <arg name="events" type="a(isvu)" direction="in"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <method name="AboutToShowGroup">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="updatesNeeded" type="ai" direction="out"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <signal name="ItemsPropertiesUpdated">
+      <arg name="updatedProps" type="a(ia{sv})"/>
+      <arg name="removedProps" type="a(ias)"/>
+    </signal>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+const ID_ROOT: i32 = 0;
+const ID_STATUS: i32 = 1;
+const ID_START: i32 = 3;
+const ID_STOP: i32 = 4;
+const ID_RESTART: i32 = 5;
+const ID_PROFILES: i32 = 7;
+const ID_SHOW: i32 = 9;
+const ID_QUIT: i32 = 10;
+const ID_PROFILE_BASE: i32 = 100;
+
+pub enum TrayAction {
+    Start,
+    Stop,
+    Restart,
+    ApplyProfile(String),
+    ShowWindow,
+    Quit,
+}
+
+#[derive(Default)]
+struct TrayState {
+    active: bool,
+    status_text: String,
+    profiles: Vec<String>,
+    active_profile: Option<String>,
+    revision: u32,
+}
+
+pub struct Tray {
+    connection: gio::DBusConnection,
+    state: Rc<RefCell<TrayState>>,
+    registrations: Vec<gio::RegistrationId>,
+    release_names: Box<dyn FnOnce()>,
+}
+
+struct MenuItem {
+    id: i32,
+    properties: HashMap<String, glib::Variant>,
+    children: Vec<i32>,
+}
+
+fn menu_items(state: &TrayState) -> Vec<MenuItem> {
+    let item = |id: i32, properties: Vec<(&str, glib::Variant)>, children: Vec<i32>| MenuItem {
+        id,
+        properties: properties.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        children,
+    };
+    let separator = |id: i32| item(id, vec![("type", "separator".to_variant())], Vec::new());
+    let profile_ids: Vec<i32> = (0..state.profiles.len() as i32).map(|i| ID_PROFILE_BASE + i).collect();
+    let mut items = vec![
+        item(ID_ROOT, vec![("children-display", "submenu".to_variant())], vec![ID_STATUS, 2, ID_START, ID_STOP, ID_RESTART, 6, ID_PROFILES, 8, ID_SHOW, ID_QUIT]),
+        item(ID_STATUS, vec![("label", state.status_text.to_variant()), ("enabled", false.to_variant())], Vec::new()),
+        separator(2),
+        item(ID_START, vec![("label", t("Başlat").to_variant()), ("visible", (!state.active).to_variant())], Vec::new()),
+        item(ID_STOP, vec![("label", t("Durdur").to_variant()), ("visible", state.active.to_variant())], Vec::new()),
+        item(ID_RESTART, vec![("label", t("Yeniden Başlat").to_variant())], Vec::new()),
+        separator(6),
+        item(
+            ID_PROFILES,
+            vec![
+                ("label", t("Profiller").to_variant()),
+                ("children-display", "submenu".to_variant()),
+                ("enabled", (!state.profiles.is_empty()).to_variant()),
+            ],
+            profile_ids,
+        ),
+        separator(8),
+        item(ID_SHOW, vec![("label", t("Pencereyi Aç").to_variant())], Vec::new()),
+        item(ID_QUIT, vec![("label", t("Çıkış").to_variant())], Vec::new()),
+    ];
+    for (i, name) in state.profiles.iter().enumerate() {
+        let selected = state.active_profile.as_deref() == Some(name.as_str());
+        items.push(item(
+            ID_PROFILE_BASE + i as i32,
+            vec![
+                ("label", name.to_variant()),
+                ("toggle-type", "radio".to_variant()),
+                ("toggle-state", (selected as i32).to_variant()),
+            ],
+            Vec::new(),
+        ));
+    }
+    items
+}
+
+fn layout_node(items: &[MenuItem], id: i32) -> glib::Variant {
+    let Some(item) = items.iter().find(|i| i.id == id) else {
+        return layout_node(items, ID_ROOT);
+    };
+    let children: Vec<glib::Variant> = item
+        .children
+        .iter()
+        .filter(|c| items.iter().any(|i| i.id == **c))
+        .map(|c| glib::Variant::from_variant(&layout_node(items, *c)))
+        .collect();
+    glib::Variant::tuple_from_iter([
+        item.id.to_variant(),
+        item.properties.to_variant(),
+        glib::Variant::array_from_iter_with_type(glib::VariantTy::VARIANT, children),
+    ])
+}
+
+fn action_for(state: &TrayState, id: i32) -> Option<TrayAction> {
+    match id {
+        ID_START => Some(TrayAction::Start),
+        ID_STOP => Some(TrayAction::Stop),
+        ID_RESTART => Some(TrayAction::Restart),
+        ID_SHOW => Some(TrayAction::ShowWindow),
+        ID_QUIT => Some(TrayAction::Quit),
+        _ if id >= ID_PROFILE_BASE => state
+            .profiles
+            .get((id - ID_PROFILE_BASE) as usize)
+            .map(|name| TrayAction::ApplyProfile(name.clone())),
+        _ => None,
+    }
+}
+
+fn tooltip(state: &TrayState) -> glib::Variant {
+    let mut description = state.status_text.clone();
+    if let Some(profile) = &state.active_profile {
+        description.push('\n');
+        description.push_str(&t("Profil: {}").replace("{}", profile));
+    }
+    glib::Variant::tuple_from_iter([
+        ICON_NAME.to_variant(),
+        glib::Variant::array_from_iter_with_type(glib::VariantTy::new("(iiay)").unwrap(), Vec::<glib::Variant>::new()),
+        "Zapret GTK".to_variant(),
+        description.to_variant(),
+    ])
+}
+
+fn item_status(state: &TrayState) -> &'static str {
+    if state.active { "Active" } else { "NeedsAttention" }
+}
+
+impl Tray {
+    pub fn start(connection: &gio::DBusConnection, on_action: impl Fn(TrayAction) + 'static) -> Result<Tray, glib::Error> {
+        let state = Rc::new(RefCell::new(TrayState::default()));
+        let on_action: Rc<dyn Fn(TrayAction)> = Rc::new(on_action);
+        let item_info = gio::DBusNodeInfo::for_xml(ITEM_XML)?
+            .lookup_interface(ITEM_INTERFACE)
+            .expect("StatusNotifierItem interface");
+        let menu_info = gio::DBusNodeInfo::for_xml(MENU_XML)?
+            .lookup_interface(MENU_INTERFACE)
+            .expect("dbusmenu interface");
+
+        let dispatch = {
+            let on_action = on_action.clone();
+            move |action: TrayAction| {
+                let on_action = on_action.clone();
+                glib::idle_add_local_once(move || on_action(action));
+            }
+        };
+
+        let item_dispatch = dispatch.clone();
+        let item_state = state.clone();
+        let item_registration = connection
+            .register_object(ITEM_PATH, &item_info)
+            .method_call(move |_, _, _, _, method, _, invocation| {
+                if method == "Activate" || method == "SecondaryActivate" {
+                    item_dispatch(TrayAction::ShowWindow);
+                }
+                invocation.return_value(None);
+            })
+            .property(move |_, _, _, _, property| {
+                let state = item_state.borrow();
+                match property {
+                    "Category" => "ApplicationStatus".to_variant(),
+                    "Id" => "zapret-gtk".to_variant(),
+                    "Title" => "Zapret GTK".to_variant(),
+                    "Status" => item_status(&state).to_variant(),
+                    "IconName" => ICON_NAME.to_variant(),
+                    "AttentionIconName" => "dialog-warning-symbolic".to_variant(),
+                    "ToolTip" => tooltip(&state),
+                    "ItemIsMenu" => false.to_variant(),
+                    "Menu" => glib::variant::ObjectPath::try_from(MENU_PATH.to_string()).unwrap().to_variant(),
+                    _ => "".to_variant(),
+                }
+            })
+            .build()?;
+
+        let menu_state = state.clone();
+        let menu_registration = connection
+            .register_object(MENU_PATH, &menu_info)
+            .method_call(move |_, _, _, _, method, params, invocation| {
+                let state = menu_state.borrow();
+                let items = menu_items(&state);
+                let reply = match method {
+                    "GetLayout" => {
+                        let parent = params.child_value(0).get::<i32>().unwrap_or(ID_ROOT);
+                        Some(glib::Variant::tuple_from_iter([state.revision.to_variant(), layout_node(&items, parent)]))
+                    },
+                    "GetGroupProperties" => {
+                        let ids = params.child_value(0).get::<Vec<i32>>().unwrap_or_default();
+                        let entries: Vec<glib::Variant> = items
+                            .iter()
+                            .filter(|i| ids.is_empty() || ids.contains(&i.id))
+                            .map(|i| glib::Variant::tuple_from_iter([i.id.to_variant(), i.properties.to_variant()]))
+                            .collect();
+                        Some(glib::Variant::tuple_from_iter([glib::Variant::array_from_iter_with_type(glib::VariantTy::new("(ia{sv})").unwrap(), entries)]))
+                    },
+                    "GetProperty" => {
+                        let id = params.child_value(0).get::<i32>().unwrap_or(ID_ROOT);
+                        let name = params.child_value(1).get::<String>().unwrap_or_default();
+                        let value = items
+                            .iter()
+                            .find(|i| i.id == id)
+                            .and_then(|i| i.properties.get(&name).cloned())
+                            .unwrap_or_else(|| "".to_variant());
+                        Some(glib::Variant::tuple_from_iter([glib::Variant::from_variant(&value)]))
+                    },
+                    "Event" => {
+                        let id = params.child_value(0).get::<i32>().unwrap_or(ID_ROOT);
+                        let event = params.child_value(1).get::<String>().unwrap_or_default();
+                        if event == "clicked" {
+                            if let Some(action) = action_for(&state, id) {
+                                dispatch(action);
+                            }
+                        }
+                        None
+                    },
+                    "EventGroup" => {
+                        let events = params.child_value(0);
+                        for i in 0..events.n_children() {
+                            let event = events.child_value(i);
+                            let id = event.child_value(0).get::<i32>().unwrap_or(ID_ROOT);
+                            if event.child_value(1).get::<String>().as_deref() == Some("clicked") {
+                                if let Some(action) = action_for(&state, id) {
+                                    dispatch(action);
+                                }
+                            }
+                        }
+                        Some((Vec::<i32>::new(),).to_variant())
+                    },
+                    "AboutToShow" => Some((false,).to_variant()),
+                    "AboutToShowGroup" => Some((Vec::<i32>::new(), Vec::<i32>::new()).to_variant()),
+                    _ => None,
+                };
+                invocation.return_value(reply.as_ref());
+            })
+            .property(|_, _, _, _, property| match property {
+                "Version" => 3u32.to_variant(),
+                "TextDirection" => "ltr".to_variant(),
+                "Status" => "normal".to_variant(),
+                "IconThemePath" => Vec::<String>::new().to_variant(),
+                _ => "".to_variant(),
+            })
+            .build()?;
+
+        let bus_name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+        let owner_id = gio::bus_own_name_on_connection(connection, &bus_name, gio::BusNameOwnerFlags::NONE, |_, _| {}, |_, name| {
+            log_to_file(&format!("Lost tray bus name {}", name));
+        });
+        let watcher_id = gio::bus_watch_name_on_connection(connection, WATCHER_NAME, gio::BusNameWatcherFlags::NONE, move |conn, _, _| {
+            conn.call(
+                Some(WATCHER_NAME),
+                WATCHER_PATH,
+                WATCHER_NAME,
+                "RegisterStatusNotifierItem",
+                Some(&(bus_name.as_str(),).to_variant()),
+                None,
+                gio::DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+                |result| {
+                    if let Err(e) = result {
+                        log_to_file(&format!("Could not register tray icon: {}", e));
+                    }
+                },
+            );
+        }, |_, _| {
+            log_to_file("No StatusNotifierWatcher on the session bus, tray icon is not shown.");
+        });
+
+        Ok(Tray {
+            connection: connection.clone(),
+            state,
+            registrations: vec![item_registration, menu_registration],
+            release_names: Box::new(move || {
+                gio::bus_unwatch_name(watcher_id);
+                gio::bus_unown_name(owner_id);
+            }),
+        })
+    }
+
+    pub fn update(&self, active: bool, status_text: &str, profiles: Vec<String>, active_profile: Option<String>) {
+        let revision = {
+            let mut state = self.state.borrow_mut();
+            let changed = state.active != active
+                || state.status_text != status_text
+                || state.profiles != profiles
+                || state.active_profile != active_profile;
+            if !changed && state.revision > 0 {
+                return;
+            }
+            state.active = active;
+            state.status_text = status_text.to_string();
+            state.profiles = profiles;
+            state.active_profile = active_profile;
+            state.revision += 1;
+            state.revision
+        };
+        let status = item_status(&self.state.borrow()).to_string();
+        let _ = self.connection.emit_signal(None, MENU_PATH, MENU_INTERFACE, "LayoutUpdated", Some(&(revision, ID_ROOT).to_variant()));
+        let _ = self.connection.emit_signal(None, ITEM_PATH, ITEM_INTERFACE, "NewStatus", Some(&(status,).to_variant()));
+        let _ = self.connection.emit_signal(None, ITEM_PATH, ITEM_INTERFACE, "NewToolTip", None);
+    }
+
+    pub fn stop(self) {
+        (self.release_names)();
+        for id in self.registrations {
+            let _ = self.connection.unregister_object(id);
+        }
+    }
+}
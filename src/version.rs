@@ -0,0 +1,235 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::{get_config_dir, settings, t};
+
+pub const DEFAULT_REMOTE: &str = "https://github.com/bol-van/zapret.git";
+
+pub struct InstalledVersion {
+    pub tag: Option<String>,
+    pub commit: String,
+}
+
+impl InstalledVersion {
+    pub fn describe(&self) -> String {
+        let short = &self.commit[..self.commit.len().min(12)];
+        match &self.tag {
+            Some(tag) if short.is_empty() => tag.clone(),
+            Some(tag) => format!("{} ({})", tag, short),
+            None => short.to_string(),
+        }
+    }
+}
+
+pub fn remote() -> String {
+    settings::get("zapret_remote")
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_string())
+}
+
+pub fn set_remote(remote: &str) -> io::Result<()> {
+    settings::set("zapret_remote", remote.trim())
+}
+
+pub fn proxy() -> Option<String> {
+    settings::get("zapret_proxy").filter(|v| !v.trim().is_empty())
+}
+
+pub fn validate_proxy(proxy: &str) -> Result<(), String> {
+    let proxy = proxy.trim();
+    if proxy.is_empty() {
+        return Ok(());
+    }
+    let valid = ["http://", "https://", "socks4://", "socks4a://", "socks5://", "socks5h://"]
+        .iter()
+        .find_map(|scheme| proxy.strip_prefix(scheme))
+        .and_then(|rest| rest.rsplit_once(':'))
+        .is_some_and(|(host, port)| !host.is_empty() && port.trim_end_matches('/').parse::<u16>().is_ok());
+    if valid {
+        Ok(())
+    } else {
+        Err(t("Geçersiz vekil sunucu: {}. Örnek: http://127.0.0.1:8080 veya socks5h://127.0.0.1:9050").replace("{}", proxy))
+    }
+}
+
+pub fn set_proxy(proxy: &str) -> Result<(), String> {
+    validate_proxy(proxy)?;
+    settings::set("zapret_proxy", proxy.trim()).map_err(|e| e.to_string())
+}
+
+pub fn network_command(program: &str) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(proxy) = proxy() {
+        for var in ["http_proxy", "https_proxy", "HTTPS_PROXY", "all_proxy", "ALL_PROXY"] {
+            cmd.env(var, &proxy);
+        }
+    }
+    cmd
+}
+
+fn is_local_path(remote: &str) -> bool {
+    !remote.contains("://") && !remote.contains('@')
+}
+
+pub fn check_reachable(remote: &str) -> Result<(), String> {
+    if is_local_path(remote) {
+        return if Path::new(remote).exists() {
+            Ok(())
+        } else {
+            Err(t("Depo bulunamadı: {}").replace("{}", remote))
+        };
+    }
+    let output = network_command("git")
+        .args(["ls-remote", "--heads", remote])
+        .env("GIT_HTTP_LOW_SPEED_LIMIT", "1000")
+        .env("GIT_HTTP_LOW_SPEED_TIME", "20")
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let reason = String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("").trim().to_string();
+    let hint = match proxy() {
+        Some(proxy) => t("Vekil sunucu ({}) ve ayna adresini denetleyin.").replace("{}", &proxy),
+        None => t("Başka bir ayna veya bir vekil sunucu deneyin."),
+    };
+    Err(t("{r} adresine ulaşılamıyor: {e}\n{h}").replace("{r}", remote).replace("{e}", &reason).replace("{h}", &hint))
+}
+
+pub fn pinned_tag() -> Option<String> {
+    settings::get("zapret_pin").filter(|v| !v.trim().is_empty())
+}
+
+pub fn set_pinned_tag(tag: &str) -> io::Result<()> {
+    settings::set("zapret_pin", tag.trim())
+}
+
+pub fn get_version_path() -> PathBuf {
+    get_config_dir().join("zapret_version.txt")
+}
+
+pub fn installed() -> Option<InstalledVersion> {
+    let content = fs::read_to_string(get_version_path()).ok()?;
+    let (tag, commit) = content.trim().split_once('\t')?;
+    Some(InstalledVersion {
+        tag: (!tag.is_empty()).then(|| tag.to_string()),
+        commit: commit.to_string(),
+    })
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn source_version(dir: &Path) -> Option<InstalledVersion> {
+    match git(dir, &["rev-parse", "HEAD"]) {
+        Some(commit) => {
+            let tag = git(dir, &["describe", "--tags", "--exact-match", "HEAD"]);
+            Some(InstalledVersion { tag, commit })
+        }
+        None => {
+            let tag = fs::read_to_string(dir.join(crate::source::TAG_MARKER)).ok()?;
+            Some(InstalledVersion { tag: Some(tag.trim().to_string()), commit: String::new() })
+        }
+    }
+}
+
+pub fn record_installed(dir: &Path) -> io::Result<()> {
+    let Some(version) = source_version(dir) else {
+        return Err(io::Error::other(t("Kaynak klasöründe sürüm bilgisi yok")));
+    };
+    fs::write(get_version_path(), format!("{}\t{}\n", version.tag.unwrap_or_default(), version.commit))
+}
+
+pub fn parse_version(tag: &str) -> Option<Vec<u32>> {
+    tag.strip_prefix('v')
+        .unwrap_or(tag)
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+fn compare_tags(a: &str, b: &str) -> Ordering {
+    parse_version(a).cmp(&parse_version(b))
+}
+
+pub fn remote_tags(remote: &str) -> Result<Vec<String>, String> {
+    let output = network_command("git")
+        .args(["ls-remote", "--tags", "--refs", remote])
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(t("Uzak depo okunamadı: {}").replace("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let mut tags: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(1)?.strip_prefix("refs/tags/").map(|s| s.to_string()))
+        .filter(|tag| parse_version(tag).is_some())
+        .collect();
+    tags.sort_by(|a, b| compare_tags(b, a));
+    Ok(tags)
+}
+
+pub fn target_tag(tags: &[String]) -> Result<Option<String>, String> {
+    match pinned_tag() {
+        Some(pin) if tags.contains(&pin) => Ok(Some(pin)),
+        Some(pin) => Err(t("Sabitlenen sürüm {} uzak depoda bulunamadı.").replace("{}", &pin)),
+        None => Ok(tags.first().cloned()),
+    }
+}
+
+// A pinned older tag counts too, so pinning can downgrade.
+pub fn needs_upgrade(installed: Option<&InstalledVersion>, target: &str) -> bool {
+    installed.and_then(|v| v.tag.as_deref()) != Some(target)
+}
+
+fn clone_args(remote: &str, tag: Option<&str>, dir: &Path) -> Vec<String> {
+    let mut args = vec!["clone".to_string()];
+    if let Some(tag) = tag {
+        args.extend(["--branch".to_string(), tag.to_string(), "--depth".to_string(), "1".to_string()]);
+    }
+    args.push(remote.to_string());
+    args.push(dir.to_string_lossy().to_string());
+    args
+}
+
+pub fn fetch_source(remote: &str, tag: Option<&str>, dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(|e| t("Eski kaynak klasörü silinemedi: {}").replace("{}", &e.to_string()))?;
+    }
+    check_reachable(remote)?;
+    let output = network_command("git")
+        .args(clone_args(remote, tag, dir))
+        .output()
+        .map_err(|e| t("Komut hatası: {}").replace("{}", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(t("Git clone hatası.") + " " + String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let verification = crate::integrity::verify_checkout(dir, tag).inspect_err(|_| {
+        let _ = fs::remove_dir_all(dir);
+    })?;
+    crate::log_to_file(&format!("zapret source verified: {:?}", verification));
+    Ok(())
+}
+
+pub fn build(dir: &Path) -> Result<(), String> {
+    let output = Command::new("make")
+        .arg("-C")
+        .arg(dir)
+        .output()
+        .map_err(|_| t("Make komutu başlatılamadı. 'make' kurulu mu?"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(t("Make hatası ({c}): {e}")
+        .replace("{c}", &output.status.code().unwrap_or(-1).to_string())
+        .replace("{e}", stderr.lines().last().unwrap_or("")))
+}